@@ -0,0 +1,165 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use chrono::{Duration, NaiveDate};
+
+/// An event read from an ICS file. `end` is inclusive.
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub summary: String,
+}
+
+/// Reads the dates and summaries of every `VEVENT` in `content`. Only the parts of RFC 5545 that
+/// are needed to import holidays are supported, and events without a readable start are ignored.
+pub fn parse_events(content: &str) -> Vec<Event> {
+    let mut events = vec![];
+    let mut current: Option<(Option<NaiveDate>, Option<NaiveDate>, String)> = None;
+
+    for line in unfold(content) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Drop parameters such as `;VALUE=DATE` from the property name.
+        let (property, parameters) = name.split_once(';').unwrap_or((name, ""));
+
+        match (property, value) {
+            ("BEGIN", "VEVENT") => current = Some((None, None, String::new())),
+            ("END", "VEVENT") => {
+                if let Some((Some(start), end, summary)) = current.take() {
+                    events.push(Event {
+                        start,
+                        end: end.unwrap_or(start).max(start),
+                        summary,
+                    });
+                }
+            }
+            ("DTSTART", _) => {
+                if let Some(event) = current.as_mut() {
+                    event.0 = parse_date(value);
+                }
+            }
+            ("DTEND", _) => {
+                if let Some(event) = current.as_mut() {
+                    // All-day events end on the morning of the day after their last day.
+                    let is_all_day = parameters.contains("VALUE=DATE") || value.len() == 8;
+                    event.1 = parse_date(value).map(|date| {
+                        if is_all_day {
+                            date - Duration::days(1)
+                        } else {
+                            date
+                        }
+                    });
+                }
+            }
+            ("SUMMARY", _) => {
+                if let Some(event) = current.as_mut() {
+                    event.2 = value.replace("\\,", ",").replace("\\;", ";");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Joins folded lines, which continue on the next line after a leading space or tab.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    lines
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn reads_all_day_events_with_exclusive_ends() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART;VALUE=DATE:20241031\r\n\
+             DTEND;VALUE=DATE:20241103\r\n\
+             SUMMARY:Diwali\\, break\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].start, date("2024-10-31"));
+        assert_eq!(events[0].end, date("2024-11-02"));
+        assert_eq!(events[0].summary, "Diwali, break");
+    }
+
+    #[test]
+    fn reads_timed_and_open_ended_events() {
+        let events = parse_events(
+            "BEGIN:VEVENT\n\
+             DTSTART:20240115T090000Z\n\
+             DTEND:20240115T170000Z\n\
+             SUMMARY:Exam\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             DTSTART;VALUE=DATE:20240126\n\
+             SUMMARY:Republic Day\n\
+             END:VEVENT\n",
+        );
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            (events[0].start, events[0].end),
+            (date("2024-01-15"), date("2024-01-15"))
+        );
+        assert_eq!(
+            (events[1].start, events[1].end),
+            (date("2024-01-26"), date("2024-01-26"))
+        );
+    }
+
+    #[test]
+    fn unfolds_lines_and_skips_events_without_a_start() {
+        let events = parse_events(
+            "BEGIN:VEVENT\n\
+             SUMMARY:No start\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             DTSTART;VALUE=DATE:20240301\n\
+             SUMMARY:Semester\n  break\n\
+             END:VEVENT\n",
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Semester break");
+    }
+}
@@ -0,0 +1,50 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+/// A minimal reader for holidays published as ICS files.
+pub mod ics;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    storage::calendar::{self, DayMode},
+    updates::profile::Profile,
+};
+
+/// Returns how `date` should be evaluated under `profile` along with the reason, or `None` if it
+/// is a regular day. Holidays take precedence over the recurring weekdays of the profile.
+pub fn day_mode(profile: &Profile, date: NaiveDate) -> anyhow::Result<Option<(DayMode, String)>> {
+    if let Some(holiday) = calendar::get(date)? {
+        return Ok(Some((holiday.mode, holiday.reason)));
+    }
+
+    let weekday = date.weekday();
+    if profile.skip_weekdays.contains(&weekday) {
+        return Ok(Some((
+            DayMode::Skip,
+            format!("Recurring skip ({})", weekday),
+        )));
+    }
+    if profile.optional_weekdays.contains(&weekday) {
+        return Ok(Some((
+            DayMode::Optional,
+            format!("Recurring optional day ({})", weekday),
+        )));
+    }
+
+    Ok(None)
+}
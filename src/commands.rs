@@ -15,10 +15,37 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
+use chrono::{Duration, NaiveDate};
+use serenity::all::{
+    Attachment, ComponentInteractionCollector, CreateActionRow, CreateAttachment, CreateButton,
+    CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
 use tracing::{info, trace};
 use tracing_subscriber::EnvFilter;
 
-use crate::{Context, Data, Error};
+use std::collections::HashMap;
+
+use crate::{
+    calendar::ics,
+    graphql::{models::Member, queries::fetch_members},
+    report::{
+        json::JsonRenderer, markdown::MarkdownRenderer, terminal::TerminalRenderer, DailyReport,
+        ReportRenderer,
+    },
+    storage::{
+        calendar::{self, DayMode, Holiday},
+        freezes as freeze_tokens,
+        history::{Outcome, Query},
+        overrides::Verdict,
+        preferences, runs,
+    },
+    updates::{
+        self, appeals,
+        overrides::{self, Decision},
+    },
+    utils::heatmap,
+    Context, Data, Error,
+};
 
 #[poise::command(prefix_command)]
 async fn amdctl(ctx: Context<'_>) -> Result<(), Error> {
@@ -57,7 +84,717 @@ async fn set_log_level(ctx: Context<'_>, level: String) -> Result<(), Error> {
     Ok(())
 }
 
+#[derive(Debug, poise::ChoiceParameter)]
+enum Toggle {
+    On,
+    Off,
+}
+
+/// Turn the reminders for unsent status updates on or off.
+#[poise::command(slash_command, prefix_command)]
+async fn reminders(ctx: Context<'_>, setting: Toggle) -> Result<(), Error> {
+    trace!("Running reminders command");
+    let enabled = matches!(setting, Toggle::On);
+    preferences::update(ctx.author().id, |p| p.reminders = enabled)?;
+
+    let reply = if enabled {
+        "You will be reminded if you haven't sent your status update by the evening."
+    } else {
+        "You will no longer be reminded about your status updates."
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Allow or stop the status update report from pinging you.
+#[poise::command(slash_command, prefix_command)]
+async fn pings(ctx: Context<'_>, setting: Toggle) -> Result<(), Error> {
+    trace!("Running pings command");
+    let enabled = matches!(setting, Toggle::On);
+    preferences::update(ctx.author().id, |p| p.pings = enabled)?;
+
+    let reply = if enabled {
+        "The status update report may ping you now."
+    } else {
+        "The status update report will no longer ping you."
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Show how many status updates a member sent and missed.
+#[poise::command(slash_command, prefix_command)]
+async fn history(
+    ctx: Context<'_>,
+    #[description = "Defaults to you"] member: Option<serenity::all::User>,
+    #[description = "First day to count, as YYYY-MM-DD"] since: Option<String>,
+    #[description = "Last day to count, as YYYY-MM-DD"] until: Option<String>,
+) -> Result<(), Error> {
+    trace!("Running history command");
+    let user = member.as_ref().unwrap_or_else(|| ctx.author());
+
+    let mut query = Query::default().discord_id(user.id.to_string());
+    for (value, is_since) in [(&since, true), (&until, false)] {
+        let Some(value) = value else {
+            continue;
+        };
+        let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") else {
+            ctx.say(format!("Invalid date {}, use YYYY-MM-DD.", value))
+                .await?;
+            return Ok(());
+        };
+        query = if is_since {
+            query.since(date)
+        } else {
+            query.until(date)
+        };
+    }
+
+    let mut sent = 0;
+    for outcome in [Outcome::Accepted, Outcome::Late, Outcome::MadeUp] {
+        sent += query.clone().outcome(outcome).count()?;
+    }
+    let missed = query.outcome(Outcome::Missed).count()?;
+    let period = match (&since, &until) {
+        (Some(since), Some(until)) => format!(" from {} to {}", since, until),
+        (Some(since), None) => format!(" since {}", since),
+        (None, Some(until)) => format!(" until {}", until),
+        (None, None) => String::new(),
+    };
+    ctx.say(format!(
+        "{} sent {} and missed {} status updates{}.",
+        user.name, sent, missed, period
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Days of history shown by [`streak`].
+const STREAK_HISTORY_DAYS: i64 = 30;
+
+/// Show a member's streak, rank and the last 30 days of status updates.
+#[poise::command(slash_command, prefix_command)]
+async fn streak(
+    ctx: Context<'_>,
+    #[description = "Defaults to you"] member: Option<serenity::all::User>,
+) -> Result<(), Error> {
+    trace!("Running streak command");
+    let user = member.as_ref().unwrap_or_else(|| ctx.author());
+    let discord_id = user.id.to_string();
+    let members = fetch_members().await?;
+    let Some(found) = members.iter().find(|m| m.discord_id == discord_id) else {
+        ctx.say(format!("{} is not a member in Root.", user.name))
+            .await?;
+        return Ok(());
+    };
+
+    let current_of = |m: &Member| m.streak.first().map_or(0, |s| s.current_streak);
+    let current = current_of(found);
+    let max = found.streak.first().map_or(0, |s| s.max_streak);
+    // Ranked among the same members as on the leaderboard.
+    let profiles = &ctx.data().profiles;
+    let ranked: Vec<&Member> = members
+        .iter()
+        .filter(|m| profiles.iter().any(|p| p.includes(m)))
+        .collect();
+    let rank = if ranked.iter().any(|m| m.member_id == found.member_id) {
+        let rank = 1 + ranked.iter().filter(|m| current_of(m) > current).count();
+        format!("#{} of {}", rank, ranked.len())
+    } else {
+        "Not ranked".to_string()
+    };
+
+    let profile = ctx
+        .data()
+        .profiles
+        .iter()
+        .find(|p| p.includes(found))
+        .or(ctx.data().profiles.first());
+    let today = profile.map_or_else(|| chrono::Utc::now().date_naive(), |p| p.schedule.today());
+    let since = today - Duration::days(STREAK_HISTORY_DAYS - 1);
+    let mut query = Query::default().discord_id(&discord_id).since(since);
+    if let Some(profile) = profile {
+        query = query.profile(&profile.name);
+    }
+    let outcomes: HashMap<NaiveDate, Outcome> = query
+        .fetch()?
+        .into_iter()
+        .map(|o| (o.date, o.outcome))
+        .collect();
+
+    let mut days = String::new();
+    for date in since.iter_days().take_while(|date| *date <= today) {
+        days.push_str(match outcomes.get(&date) {
+            Some(Outcome::Accepted | Outcome::MadeUp) => ":green_square:",
+            Some(Outcome::Late) => ":yellow_square:",
+            Some(Outcome::Missed) => ":red_square:",
+            Some(Outcome::Frozen) => ":ice_cube:",
+            Some(Outcome::Excused) | None => ":white_large_square:",
+        });
+    }
+    let hits = outcomes
+        .values()
+        .filter(|o| matches!(o, Outcome::Accepted | Outcome::Late | Outcome::MadeUp))
+        .count();
+    let misses = outcomes.values().filter(|o| **o == Outcome::Missed).count();
+
+    let embed = CreateEmbed::default()
+        .title(format!("{}'s Streak", found.name))
+        .description(format!(
+            "**Current streak:** {}\n**Max streak:** {}\n**Rank:** {}\n\n\
+             **Last {} days** ({} sent, {} missed)\n{}",
+            current, max, rank, STREAK_HISTORY_DAYS, hits, misses, days
+        ))
+        .footer(CreateEmbedFooter::new(format!("{} to {}", since, today)))
+        .color(serenity::all::Colour::new(0xeab308));
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show a year of status updates as a heatmap, either of a member or of a whole group.
+#[poise::command(slash_command, prefix_command)]
+async fn heatmap(
+    ctx: Context<'_>,
+    #[description = "Defaults to you"] member: Option<serenity::all::User>,
+    #[description = "Show the submission rate of a group instead"] group: Option<i32>,
+) -> Result<(), Error> {
+    trace!("Running heatmap command");
+    let today = ctx
+        .data()
+        .profiles
+        .first()
+        .map_or_else(|| chrono::Utc::now().date_naive(), |p| p.schedule.today());
+    let query = Query::default().since(today - Duration::days(371));
+
+    let (title, legend, png) = match group {
+        Some(group) => {
+            let mut days: HashMap<NaiveDate, (u32, u32)> = HashMap::new();
+            for outcome in query.fetch()? {
+                if outcome.group_id != Some(group) {
+                    continue;
+                }
+                let (sent, expected) = days.entry(outcome.date).or_default();
+                match outcome.outcome {
+                    Outcome::Accepted | Outcome::Late | Outcome::MadeUp => {
+                        *sent += 1;
+                        *expected += 1;
+                    }
+                    Outcome::Missed | Outcome::Frozen => *expected += 1,
+                    Outcome::Excused => {}
+                }
+            }
+            let png = heatmap::render(today, |date| match days.get(&date) {
+                Some((sent, expected)) if *expected > 0 => {
+                    heatmap::level(*sent as f64 / *expected as f64)
+                }
+                _ => heatmap::EMPTY,
+            })?;
+            (
+                format!("Group {}", group),
+                "Darker days had fewer updates sent.",
+                png,
+            )
+        }
+        None => {
+            let user = member.as_ref().unwrap_or_else(|| ctx.author());
+            let outcomes: HashMap<NaiveDate, Outcome> = query
+                .discord_id(user.id.to_string())
+                .fetch()?
+                .into_iter()
+                .map(|o| (o.date, o.outcome))
+                .collect();
+            let png = heatmap::render(today, |date| match outcomes.get(&date) {
+                Some(Outcome::Accepted | Outcome::MadeUp) => heatmap::LEVELS[3],
+                Some(Outcome::Late) => heatmap::LEVELS[1],
+                Some(Outcome::Missed) => heatmap::MISSED,
+                Some(Outcome::Frozen) => heatmap::FROZEN,
+                Some(Outcome::Excused) | None => heatmap::EMPTY,
+            })?;
+            (
+                user.name.clone(),
+                "Green days were sent, dim green ones late, red ones missed and blue ones frozen.",
+                png,
+            )
+        }
+    };
+
+    let embed = CreateEmbed::default()
+        .title(format!("{}'s Status Updates", title))
+        .description(legend)
+        .image("attachment://heatmap.png")
+        .color(serenity::all::Colour::new(0xeab308));
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .attachment(CreateAttachment::bytes(png, "heatmap.png")),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Show how many streak freeze tokens a member has left.
+#[poise::command(slash_command, prefix_command)]
+async fn freezes(
+    ctx: Context<'_>,
+    #[description = "Defaults to you"] member: Option<serenity::all::User>,
+) -> Result<(), Error> {
+    trace!("Running freezes command");
+    let user = member.as_ref().unwrap_or_else(|| ctx.author());
+    let balance = freeze_tokens::get(&user.id.to_string())?;
+
+    let mut reply = format!(
+        "{} has {} streak freeze token(s).",
+        user.name, balance.tokens
+    );
+    if let Some(policy) = ctx.data().profiles.iter().find_map(|p| p.freezes.as_ref()) {
+        reply.push_str(&format!(
+            " {} more consecutive update(s) until the next one, holding at most {}.",
+            policy.every.saturating_sub(balance.progress),
+            policy.cap
+        ));
+    }
+    ctx.say(reply).await?;
+    Ok(())
+}
+
+/// Only lets members with the role in `MENTOR_ROLE_ID` run a command.
+async fn is_mentor(ctx: Context<'_>) -> Result<bool, Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(false);
+    };
+
+    Ok(updates::is_mentor(ctx.serenity_context(), guild_id, ctx.author().id).await?)
+}
+
+/// Appeal a missed status update, such as one sent in the wrong channel.
+#[poise::command(slash_command)]
+async fn appeal(
+    ctx: Context<'_>,
+    #[description = "Link to the update that should have counted"] message_link: String,
+) -> Result<(), Error> {
+    trace!("Running appeal command");
+    let reply = appeals::file(
+        ctx.serenity_context(),
+        ctx.data(),
+        ctx.author(),
+        &message_link,
+    )
+    .await?;
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Count a message as a status update, even if it doesn't follow the rules.
+#[poise::command(context_menu_command = "Count as status update", check = "is_mentor")]
+async fn count_update(ctx: Context<'_>, msg: serenity::all::Message) -> Result<(), Error> {
+    trace!("Running count_update command");
+    override_update(ctx, msg, Verdict::Accept).await
+}
+
+/// Keep a message from counting as a status update, even if it follows the rules.
+#[poise::command(context_menu_command = "Reject status update", check = "is_mentor")]
+async fn reject_update(ctx: Context<'_>, msg: serenity::all::Message) -> Result<(), Error> {
+    trace!("Running reject_update command");
+    override_update(ctx, msg, Verdict::Reject).await
+}
+
+async fn override_update(
+    ctx: Context<'_>,
+    mut msg: serenity::all::Message,
+    verdict: Verdict,
+) -> Result<(), Error> {
+    msg.guild_id = msg.guild_id.or(ctx.guild_id());
+    let reply = match overrides::decide(ctx.data(), &msg, verdict, ctx.author())? {
+        Decision::Recorded => {
+            let action = match verdict {
+                Verdict::Accept => "will count",
+                Verdict::Reject => "won't count",
+            };
+            format!(
+                "{}'s message {} as their status update. This is listed in the next report.",
+                msg.author.name, action
+            )
+        }
+        Decision::NotAnUpdate => "That message isn't in a status update channel.".to_string(),
+        Decision::AlreadyCounts => format!(
+            "{}'s message already counts as their status update.",
+            msg.author.name
+        ),
+    };
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Manage the days on which status updates are skipped or optional.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    check = "is_mentor",
+    subcommands("holiday_add", "holiday_remove", "holiday_list", "holiday_import")
+)]
+async fn holiday(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a holiday, or a range of holidays.
+#[poise::command(slash_command, prefix_command, rename = "add", check = "is_mentor")]
+async fn holiday_add(
+    ctx: Context<'_>,
+    #[description = "First day, as YYYY-MM-DD"] start: String,
+    #[description = "Last day, as YYYY-MM-DD. Defaults to the first day"] end: Option<String>,
+    #[description = "Whether updates are skipped or optional"] mode: DayMode,
+    #[description = "Shown in the report"] reason: String,
+) -> Result<(), Error> {
+    trace!("Running holiday add command");
+    let Some((start, end)) = parse_range(ctx, &start, end.as_deref()).await? else {
+        return Ok(());
+    };
+
+    let holidays: Vec<Holiday> = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| Holiday {
+            date,
+            mode,
+            reason: reason.clone(),
+        })
+        .collect();
+    let count = holidays.len();
+    calendar::add(holidays)?;
+
+    ctx.say(format!("Added {} day(s) from {} to {}.", count, start, end))
+        .await?;
+    Ok(())
+}
+
+/// Remove a holiday, or a range of holidays.
+#[poise::command(slash_command, prefix_command, rename = "remove", check = "is_mentor")]
+async fn holiday_remove(
+    ctx: Context<'_>,
+    #[description = "First day, as YYYY-MM-DD"] start: String,
+    #[description = "Last day, as YYYY-MM-DD. Defaults to the first day"] end: Option<String>,
+) -> Result<(), Error> {
+    trace!("Running holiday remove command");
+    let Some((start, end)) = parse_range(ctx, &start, end.as_deref()).await? else {
+        return Ok(());
+    };
+
+    let count = calendar::remove(start, end)?;
+    ctx.say(format!("Removed {} day(s).", count)).await?;
+    Ok(())
+}
+
+/// List the upcoming holidays.
+#[poise::command(slash_command, prefix_command, rename = "list", check = "is_mentor")]
+async fn holiday_list(ctx: Context<'_>) -> Result<(), Error> {
+    trace!("Running holiday list command");
+    // A day early, so that today is included regardless of the timezone of the profiles.
+    let since = chrono::Utc::now().date_naive() - Duration::days(1);
+    let holidays = calendar::since(since)?;
+    if holidays.is_empty() {
+        ctx.say("There are no upcoming holidays.").await?;
+        return Ok(());
+    }
+
+    let mut reply = String::from("## Upcoming Holidays\n");
+    for holiday in holidays.iter().take(25) {
+        reply.push_str(&format!(
+            "- {} | {:?} | {}\n",
+            holiday.date, holiday.mode, holiday.reason
+        ));
+    }
+    if holidays.len() > 25 {
+        reply.push_str(&format!("...and {} more.\n", holidays.len() - 25));
+    }
+    ctx.say(reply).await?;
+    Ok(())
+}
+
+/// Import the events of an ICS file as holidays.
+#[poise::command(slash_command, prefix_command, rename = "import", check = "is_mentor")]
+async fn holiday_import(
+    ctx: Context<'_>,
+    #[description = "The .ics file to import"] file: Attachment,
+    #[description = "Whether updates are skipped or optional"] mode: DayMode,
+) -> Result<(), Error> {
+    trace!("Running holiday import command");
+    let content = String::from_utf8(file.download().await?)?;
+    let holidays: Vec<Holiday> = ics::parse_events(&content)
+        .into_iter()
+        .flat_map(|event| {
+            event
+                .start
+                .iter_days()
+                .take_while(move |date| *date <= event.end)
+                .map(move |date| Holiday {
+                    date,
+                    mode,
+                    reason: event.summary.clone(),
+                })
+        })
+        .collect();
+
+    let count = holidays.len();
+    calendar::add(holidays)?;
+    ctx.say(format!("Imported {} day(s) from {}.", count, file.filename))
+        .await?;
+    Ok(())
+}
+
+/// Parses an inclusive range of dates, replying with an error if either of them is invalid.
+async fn parse_range(
+    ctx: Context<'_>,
+    start: &str,
+    end: Option<&str>,
+) -> Result<Option<(NaiveDate, NaiveDate)>, Error> {
+    let parse = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d");
+    let (Ok(start), Ok(end)) = (parse(start), end.map_or(parse(start), parse)) else {
+        ctx.say("Invalid date, use YYYY-MM-DD.").await?;
+        return Ok(None);
+    };
+    if end < start {
+        ctx.say("The last day can't be before the first day.")
+            .await?;
+        return Ok(None);
+    }
+
+    Ok(Some((start, end)))
+}
+
+#[derive(Debug, poise::ChoiceParameter)]
+enum ReportFormat {
+    Markdown,
+    #[name = "JSON"]
+    Json,
+    Text,
+}
+
+/// Export a status update report as a file.
+#[poise::command(slash_command, prefix_command, check = "is_mentor")]
+async fn report(
+    ctx: Context<'_>,
+    #[description = "Day of the report, as YYYY-MM-DD. Defaults to the latest"] date: Option<
+        String,
+    >,
+    #[description = "Defaults to markdown"] format: Option<ReportFormat>,
+    #[description = "Defaults to the first profile"] profile: Option<String>,
+) -> Result<(), Error> {
+    trace!("Running report command");
+    let profiles = &ctx.data().profiles;
+    let Some(profile) = profiles
+        .iter()
+        .find(|p| profile.as_ref().is_none_or(|name| &p.name == name))
+    else {
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        ctx.say(format!(
+            "Unknown profile, use one of: {}.",
+            names.join(", ")
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let run = match &date {
+        Some(date) => {
+            let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                ctx.say("Invalid date, use YYYY-MM-DD.").await?;
+                return Ok(());
+            };
+            runs::get(&profile.name, date)?.filter(|r| r.planned)
+        }
+        None => runs::latest(&profile.name)?,
+    };
+    let Some(run) = run else {
+        ctx.say("There is no such report.").await?;
+        return Ok(());
+    };
+
+    let daily_report = DailyReport::from_run(&run);
+    let (content, extension) = match format.unwrap_or(ReportFormat::Markdown) {
+        ReportFormat::Markdown => (MarkdownRenderer.render(&daily_report), "md"),
+        ReportFormat::Json => (JsonRenderer.render(&daily_report)?, "json"),
+        ReportFormat::Text => (TerminalRenderer.render(&daily_report), "txt"),
+    };
+    let file_name = format!("report-{}-{}.{}", run.profile, run.date, extension);
+    ctx.send(poise::CreateReply::default().attachment(CreateAttachment::bytes(content, file_name)))
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+enum LeaderboardKind {
+    Current,
+    #[name = "All time"]
+    AllTime,
+}
+
+/// Members shown on one page of the leaderboard.
+const LEADERBOARD_PAGE_SIZE: usize = 10;
+/// How long the leaderboard's buttons keep working.
+const LEADERBOARD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Show the members with the longest streaks.
+#[poise::command(slash_command, prefix_command)]
+async fn leaderboard(
+    ctx: Context<'_>,
+    #[description = "Defaults to current streaks"] kind: Option<LeaderboardKind>,
+    #[description = "Only show members of this group"] group: Option<i32>,
+) -> Result<(), Error> {
+    trace!("Running leaderboard command");
+    let kind = kind.unwrap_or(LeaderboardKind::Current);
+    let streak_of = |member: &Member| {
+        member.streak.first().map_or(0, |s| match kind {
+            LeaderboardKind::Current => s.current_streak,
+            LeaderboardKind::AllTime => s.max_streak,
+        })
+    };
+
+    // Only members who are expected to send updates under some profile are ranked.
+    let profiles = &ctx.data().profiles;
+    let mut members: Vec<Member> = fetch_members()
+        .await?
+        .into_iter()
+        .filter(|m| profiles.iter().any(|p| p.includes(m)))
+        .filter(|m| group.is_none_or(|g| m.group_id == Some(g)))
+        .collect();
+    if members.is_empty() {
+        ctx.say("There are no members to rank.").await?;
+        return Ok(());
+    }
+    members.sort_by_key(|m| std::cmp::Reverse(streak_of(m)));
+
+    // Members with the same streak share a rank.
+    let mut ranked: Vec<(usize, &Member, i32)> = vec![];
+    for (i, member) in members.iter().enumerate() {
+        let streak = streak_of(member);
+        let rank = match ranked.last() {
+            Some((rank, _, previous)) if *previous == streak => *rank,
+            _ => i + 1,
+        };
+        ranked.push((rank, member, streak));
+    }
+
+    let author_id = ctx.author().id.to_string();
+    let own_index = ranked
+        .iter()
+        .position(|(_, m, _)| m.discord_id == author_id);
+    let pages = ranked.len().div_ceil(LEADERBOARD_PAGE_SIZE);
+    let title = match (kind, group) {
+        (LeaderboardKind::Current, None) => "Current Streaks".to_string(),
+        (LeaderboardKind::AllTime, None) => "All Time Streaks".to_string(),
+        (LeaderboardKind::Current, Some(g)) => format!("Current Streaks - Group {}", g),
+        (LeaderboardKind::AllTime, Some(g)) => format!("All Time Streaks - Group {}", g),
+    };
+    let render_page = |page: usize| {
+        let mut description = String::new();
+        for (i, (rank, member, streak)) in ranked
+            .iter()
+            .enumerate()
+            .skip(page * LEADERBOARD_PAGE_SIZE)
+            .take(LEADERBOARD_PAGE_SIZE)
+        {
+            if Some(i) == own_index {
+                description.push_str(&format!(
+                    "**#{} {} - {}** :point_left:\n",
+                    rank, member.name, streak
+                ));
+            } else {
+                description.push_str(&format!("#{} {} - {}\n", rank, member.name, streak));
+            }
+        }
+
+        let mut footer = format!("Page {} of {}", page + 1, pages);
+        if let Some(i) = own_index {
+            footer.push_str(&format!(" | You are #{} of {}", ranked[i].0, ranked.len()));
+        }
+        CreateEmbed::default()
+            .title(&title)
+            .description(description)
+            .footer(CreateEmbedFooter::new(footer))
+            .color(serenity::all::Colour::new(0xeab308))
+    };
+
+    // Start on the invoking member's page, so that they can see their own rank.
+    let mut page = own_index.map_or(0, |i| i / LEADERBOARD_PAGE_SIZE);
+    let ctx_id = ctx.id();
+    let previous_id = format!("{}previous", ctx_id);
+    let next_id = format!("{}next", ctx_id);
+    let buttons = |page: usize| {
+        vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(&previous_id)
+                .label("Previous")
+                .disabled(page == 0),
+            CreateButton::new(&next_id)
+                .label("Next")
+                .disabled(page + 1 >= pages),
+        ])]
+    };
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .embed(render_page(page))
+                .components(buttons(page)),
+        )
+        .await?;
+
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(LEADERBOARD_TIMEOUT)
+        .await
+    {
+        if press.data.custom_id == next_id {
+            page = (page + 1).min(pages - 1);
+        } else if press.data.custom_id == previous_id {
+            page = page.saturating_sub(1);
+        }
+
+        press
+            .create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(render_page(page))
+                        .components(buttons(page)),
+                ),
+            )
+            .await?;
+    }
+
+    // Disable the buttons once they stop working.
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .embed(render_page(page))
+                .components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
+
 /// Returns a vector containg [Poise Commands][`poise::Command`]
 pub fn get_commands() -> Vec<poise::Command<Data, Error>> {
-    vec![amdctl(), set_log_level()]
+    vec![
+        amdctl(),
+        set_log_level(),
+        reminders(),
+        pings(),
+        history(),
+        streak(),
+        heatmap(),
+        holiday(),
+        freezes(),
+        report(),
+        leaderboard(),
+        appeal(),
+        count_update(),
+        reject_update(),
+    ]
 }
@@ -15,9 +15,9 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Streak {
     #[serde(rename = "currentStreak")]
     pub current_streak: i32,
@@ -26,13 +26,16 @@ pub struct Streak {
 }
 
 /// Represents a record of the Member relation in [Root][https://www.github.com/amfoss/root].
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Member {
     #[serde(rename = "memberId")]
     pub member_id: i32,
     pub name: String,
     #[serde(rename = "discordId")]
     pub discord_id: String,
+    #[serde(rename = "groupId")]
+    pub group_id: Option<i32>,
+    pub year: Option<i32>,
     #[serde(default)]
     pub streak: Vec<Streak>, // Note that Root will NOT have multiple Streak elements but it may be an empty list which is why we use a vector here
 }
@@ -30,6 +30,8 @@ pub async fn fetch_members() -> anyhow::Result<Vec<Member>> {
             memberId
             name
             discordId
+            groupId
+            year
             streak {
               currentStreak
               maxStreak
@@ -103,23 +105,51 @@ pub async fn increment_streak(member: &mut Member) -> anyhow::Result<()> {
             response.status()
         ));
     }
-    debug!("Response: {:?}", response.text().await);
+    let response_json: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse response JSON")?;
+    debug!("Response: {}", response_json);
+
+    let current_streak = response_json
+        .get("data")
+        .and_then(|data| data.get("incrementStreak"))
+        .and_then(|data| data.get("currentStreak"))
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| anyhow!("Failed to access data from {}", response_json))?
+        as i32;
 
-    // Handle the streak vector
+    // Update the member's streak vector
     if member.streak.is_empty() {
-        // If the streak vector is empty, add a new Streak object with both values set to 1
         member.streak.push(Streak {
-            current_streak: 1,
-            max_streak: 1,
+            current_streak,
+            max_streak: current_streak,
         });
     } else {
-        // Otherwise, increment the current_streak for each Streak and update max_streak if necessary
         for streak in &mut member.streak {
-            streak.current_streak += 1;
-            if streak.current_streak > streak.max_streak {
-                streak.max_streak = streak.current_streak;
-            }
+            streak.current_streak = current_streak;
+            streak.max_streak = streak.max_streak.max(current_streak);
+        }
+    }
+
+    Ok(())
+}
+
+/// Increments the streak of `member` until Root reports it at `target`. Root can only increment
+/// streaks, so a lost streak is rebuilt one day at a time.
+pub async fn restore_streak(member: &mut Member, target: i32) -> anyhow::Result<()> {
+    let mut current = member.streak.first().map_or(0, |s| s.current_streak);
+    while current < target {
+        increment_streak(member).await?;
+        let incremented = member.streak.first().map_or(0, |s| s.current_streak);
+        if incremented <= current {
+            return Err(anyhow!(
+                "Streak of {} stayed at {} after incrementing it",
+                member.name,
+                incremented
+            ));
         }
+        current = incremented;
     }
 
     Ok(())
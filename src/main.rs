@@ -15,23 +15,31 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
+/// Holidays and recurring days off that change how a day's status updates are evaluated.
+mod calendar;
 /// Contains all the commands for the bot.
 mod commands;
 /// Interact with [Root's](https://www.github.com/amfoss/root) GraphQL interace.
 mod graphql;
 /// Contains Discord IDs that may be needed across the bot.
 mod ids;
+/// The status update report, independent of where it is shown, and the renderers for each place.
+mod report;
 /// This module is a simple cron equivalent. It spawns threads for the [`Task`]s that need to be completed.
 mod scheduler;
+/// Persists amD's local state, such as recorded status updates, to disk.
+mod storage;
 /// A trait to define a job that needs to be executed regularly, for example checking for status updates daily.
 mod tasks;
+/// Detects status updates as they are posted in the update channels.
+mod updates;
 /// Misc. helper functions that don't really have a place anywhere else.
 mod utils;
 
 use anyhow::Context as _;
 use poise::{Context as PoiseContext, Framework, FrameworkOptions, PrefixFrameworkOptions};
 use serenity::{
-    all::{Reaction, ReactionType, RoleId, UserId},
+    all::{Interaction, Reaction, ReactionType, RoleId, UserId},
     client::{Context as SerenityContext, FullEvent},
     model::{gateway::GatewayIntents, id::MessageId},
 };
@@ -49,6 +57,10 @@ use ids::{
     AI_ROLE_ID, ARCHIVE_ROLE_ID, DEVOPS_ROLE_ID, MOBILE_ROLE_ID, RESEARCH_ROLE_ID,
     ROLES_MESSAGE_ID, SYSTEMS_ROLE_ID, WEB_ROLE_ID,
 };
+use updates::{
+    feedback::Feedback,
+    profile::{load_profiles, Profile},
+};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = PoiseContext<'a, Data, Error>;
@@ -57,6 +69,8 @@ pub type ReloadHandle = Arc<RwLock<reload::Handle<EnvFilter, Registry>>>;
 pub struct Data {
     pub reaction_roles: HashMap<ReactionType, RoleId>,
     pub log_reload_handle: ReloadHandle,
+    pub feedback: Feedback,
+    pub profiles: Vec<Profile>,
 }
 
 /// This function is responsible for adding all the (emoji, role_id) pairs used in the
@@ -153,6 +167,8 @@ async fn main() -> Result<(), Error> {
     let mut data = Data {
         reaction_roles: HashMap::new(),
         log_reload_handle: reload_handle,
+        feedback: Feedback::from_env().context("Failed to configure update feedback")?,
+        profiles: load_profiles().context("Failed to load status update profiles")?,
     };
     populate_data_with_reaction_roles(&mut data);
 
@@ -180,7 +196,7 @@ async fn main() -> Result<(), Error> {
         .setup(|ctx, _ready, framework| {
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                scheduler::run_scheduler(ctx.clone()).await;
+                scheduler::run_scheduler(ctx.clone(), &data.profiles).await?;
                 Ok(data)
             })
         })
@@ -204,7 +220,7 @@ async fn main() -> Result<(), Error> {
     Ok(())
 }
 
-/// Handles various events from Discord, such as reactions.
+/// Handles various events from Discord, such as reactions and messages in the update channels.
 async fn event_handler(
     ctx: &SerenityContext,
     event: &FullEvent,
@@ -212,11 +228,66 @@ async fn event_handler(
     data: &Data,
 ) -> Result<(), Error> {
     match event {
+        FullEvent::Ready { .. } => {
+            if let Err(e) = updates::backfill(ctx, data).await {
+                error!("Could not backfill updates. Error: {:?}", e);
+            }
+        }
         FullEvent::ReactionAdd { add_reaction } => {
             handle_reaction(ctx, add_reaction, data, true).await;
+            if let Err(e) = updates::overrides::handle_reaction(ctx, data, add_reaction, true).await
+            {
+                error!(
+                    "Could not handle override on message {}. Error: {}",
+                    add_reaction.message_id, e
+                );
+            }
         }
         FullEvent::ReactionRemove { removed_reaction } => {
             handle_reaction(ctx, removed_reaction, data, false).await;
+            if let Err(e) =
+                updates::overrides::handle_reaction(ctx, data, removed_reaction, false).await
+            {
+                error!(
+                    "Could not handle override on message {}. Error: {}",
+                    removed_reaction.message_id, e
+                );
+            }
+        }
+        FullEvent::Message { new_message } => {
+            if let Err(e) = updates::handle_message(ctx, data, new_message).await {
+                error!("Could not handle message {}. Error: {}", new_message.id, e);
+            }
+        }
+        FullEvent::MessageUpdate { new, event, .. } => {
+            if let Err(e) = updates::handle_message_update(ctx, data, new.as_ref(), event).await {
+                error!(
+                    "Could not handle edit of message {}. Error: {}",
+                    event.id, e
+                );
+            }
+        }
+        FullEvent::MessageDelete {
+            channel_id,
+            deleted_message_id,
+            ..
+        } => {
+            if let Err(e) = updates::handle_message_delete(data, *channel_id, *deleted_message_id) {
+                error!(
+                    "Could not handle deletion of message {}. Error: {}",
+                    deleted_message_id, e
+                );
+            }
+        }
+        FullEvent::InteractionCreate {
+            interaction: Interaction::Component(component),
+        } => {
+            if let Err(e) = updates::appeals::handle_component(ctx, data, component).await {
+                error!(
+                    "Could not handle button {}. Error: {:?}",
+                    component.data.custom_id, e
+                );
+            }
         }
         _ => {}
     }
@@ -0,0 +1,225 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::Deserialize;
+use serenity::all::{
+    ChannelId, Colour, CreateAllowedMentions, CreateEmbedAuthor, CreateMessage, Timestamp, UserId,
+};
+
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    layout, markdown, DailyReport, GroupSummary, ReportMember, ReportRenderer, ReportSection,
+};
+
+const TITLE_URL: &str = "https://www.youtube.com/watch?v=epnuvyNj0FM";
+const IMAGE_URL: &str = "https://media1.tenor.com/m/zAHCPvoyjNIAAAAd/yay-kitty.gif";
+pub const AUTHOR_URL: &str = "https://github.com/amfoss/amd";
+/// Keeps the content of a ping well below Discord's limit of 2000 characters, with mentions being
+/// at most 24 characters long.
+const MENTIONS_PER_MESSAGE: usize = 75;
+/// Longest list of update links that is added to the report itself in [`LinkMode::Inline`].
+const INLINE_LINKS: usize = 15;
+pub const ICON_URL: &str = "https://cdn.discordapp.com/avatars/1245352445736128696/da3c6f833b688f5afa875c9df5d86f91.webp?size=160";
+
+/// How a profile's report refers to members. Members are only pinged in message content, since
+/// mentions in embeds never notify anyone.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MentionPolicy {
+    /// Sections that list members as mentions instead of names.
+    pub mention: Vec<ReportSection>,
+    /// Sections whose members are pinged below the report.
+    pub ping: Vec<ReportSection>,
+    /// Only ping members who opted in with `/pings on`.
+    pub opt_in: bool,
+}
+
+impl Default for MentionPolicy {
+    fn default() -> Self {
+        Self {
+            mention: vec![],
+            ping: vec![],
+            opt_in: true,
+        }
+    }
+}
+
+/// Where a profile's report links to the update that counted for each member.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMode {
+    #[default]
+    None,
+    /// In the report, or in a thread on it if the list is long.
+    Inline,
+    /// Always in a thread on the report.
+    Thread,
+}
+
+/// How a profile's report breaks results down by Root group.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GroupBreakdown {
+    /// Adds a section per group to the report.
+    pub in_report: bool,
+    /// The channel each group's section is also posted to, so that group leads see their own
+    /// numbers.
+    pub channels: HashMap<i32, ChannelId>,
+}
+
+/// Renders a report as the messages that are posted to the report channel. Long reports are split
+/// over several embeds, and the members that should be pinged are pinged in the last messages.
+pub struct DiscordRenderer<'a> {
+    pub mentions: &'a MentionPolicy,
+    /// Members who opted in to pings.
+    pub opted_in: HashSet<UserId>,
+    pub links: LinkMode,
+    /// Whether to add the per-group breakdown to the report.
+    pub groups: bool,
+}
+
+impl ReportRenderer for DiscordRenderer<'_> {
+    type Output = Vec<CreateMessage>;
+
+    fn render(&self, report: &DailyReport) -> Vec<CreateMessage> {
+        let show_image = report.skip_reason.is_none() && report.missed.is_empty();
+        let file_name = format!("report-{}-{}.md", report.profile, report.date);
+        let mut body = markdown::body_with(report, |section, member| self.name(section, member));
+        if self.groups {
+            body.push_str(&markdown::groups(report, |section, member| {
+                self.name(section, member)
+            }));
+        }
+        if self.links == LinkMode::Inline && report.updates.len() <= INLINE_LINKS {
+            body.push_str(&markdown::links(report, |section, member| {
+                self.name(section, member)
+            }));
+        }
+
+        let mut messages: Vec<CreateMessage> =
+            layout::layout(&report.title(), &body, &file_name, |embed| {
+                let embed = embed
+                    .url(TITLE_URL)
+                    .color(Colour::new(0xeab308))
+                    .timestamp(Timestamp::now())
+                    .author(author());
+                if show_image {
+                    embed.image(IMAGE_URL)
+                } else {
+                    embed
+                }
+            })
+            .into_iter()
+            .map(|message| message.allowed_mentions(CreateAllowedMentions::new()))
+            .collect();
+        messages.extend(self.pings(report));
+
+        messages
+    }
+}
+
+impl DiscordRenderer<'_> {
+    /// Returns the messages of the thread that is started on the report, which list the links
+    /// that didn't fit in the report itself. Empty if there is no need for a thread.
+    pub fn thread(&self, report: &DailyReport) -> Vec<CreateMessage> {
+        let needs_thread = match self.links {
+            LinkMode::None => false,
+            LinkMode::Inline => report.updates.len() > INLINE_LINKS,
+            LinkMode::Thread => true,
+        };
+        if !needs_thread || report.updates.is_empty() {
+            return vec![];
+        }
+
+        let body = markdown::links(report, |section, member| self.name(section, member));
+        let file_name = format!("updates-{}-{}.md", report.profile, report.date);
+        layout::layout("Accepted Updates", &body, &file_name, |embed| {
+            embed.color(Colour::new(0xeab308)).author(author())
+        })
+        .into_iter()
+        .map(|message| message.allowed_mentions(CreateAllowedMentions::new()))
+        .collect()
+    }
+
+    /// Returns the message posted to the channel of `group`. Members are never pinged by it.
+    pub fn group(&self, report: &DailyReport, group: &GroupSummary) -> Vec<CreateMessage> {
+        let body = markdown::group_section(group, |section, member| self.name(section, member));
+        let file_name = format!(
+            "report-{}-{}-{}.md",
+            report.profile,
+            report.date,
+            group.group.unwrap_or_default()
+        );
+        layout::layout(
+            &format!("{} - {}", report.title(), group.label()),
+            &body,
+            &file_name,
+            |embed| {
+                embed
+                    .url(TITLE_URL)
+                    .color(Colour::new(0xeab308))
+                    .timestamp(Timestamp::now())
+                    .author(author())
+            },
+        )
+        .into_iter()
+        .map(|message| message.allowed_mentions(CreateAllowedMentions::new()))
+        .collect()
+    }
+
+    fn name(&self, section: ReportSection, member: &ReportMember) -> String {
+        if self.mentions.mention.contains(&section) {
+            format!("<@{}>", member.discord_id)
+        } else {
+            markdown::escape(&member.name)
+        }
+    }
+
+    /// Returns the messages that ping the members of the sections in [`MentionPolicy::ping`],
+    /// allowing mentions of exactly those members.
+    fn pings(&self, report: &DailyReport) -> Vec<CreateMessage> {
+        let mut messages = vec![];
+        for section in &self.mentions.ping {
+            let users: Vec<UserId> = report
+                .members_in(*section)
+                .into_iter()
+                .filter_map(|m| m.discord_id.parse().ok().map(UserId::new))
+                .filter(|id| !self.mentions.opt_in || self.opted_in.contains(id))
+                .collect();
+
+            // Long lists are split over several messages.
+            for chunk in users.chunks(MENTIONS_PER_MESSAGE) {
+                let mentions: Vec<String> = chunk.iter().map(|id| format!("<@{}>", id)).collect();
+                messages.push(
+                    CreateMessage::new()
+                        .content(format!("{}: {}", section.label(), mentions.join(" ")))
+                        .allowed_mentions(CreateAllowedMentions::new().users(chunk.to_vec())),
+                );
+            }
+        }
+
+        messages
+    }
+}
+
+/// The author shown on every embed amD posts.
+pub fn author() -> CreateEmbedAuthor {
+    CreateEmbedAuthor::new("amD")
+        .url(AUTHOR_URL)
+        .icon_url(ICON_URL)
+}
@@ -0,0 +1,31 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+
+use super::{DailyReport, ReportRenderer};
+
+/// Renders a report as pretty-printed JSON.
+pub struct JsonRenderer;
+
+impl ReportRenderer for JsonRenderer {
+    type Output = anyhow::Result<String>;
+
+    fn render(&self, report: &DailyReport) -> Self::Output {
+        serde_json::to_string_pretty(report).context("Failed to serialize report")
+    }
+}
@@ -0,0 +1,234 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serenity::all::{CreateAttachment, CreateEmbed, CreateMessage};
+use tracing::debug;
+
+/// Discord's limits, in characters.
+const DESCRIPTION_LIMIT: usize = 4096;
+const TITLE_LIMIT: usize = 256;
+/// The combined length of every embed in one message.
+const MESSAGE_LIMIT: usize = 6000;
+/// Room left in every embed for the author name and footer that `decorate` adds, which count
+/// towards [`MESSAGE_LIMIT`] as well.
+const DECORATION_LENGTH: usize = 256;
+const EMBEDS_PER_MESSAGE: usize = 10;
+/// Longer reports are attached as a file instead of flooding the channel.
+const MAX_MESSAGES: usize = 4;
+
+/// Lays `body` out over as many embeds and messages as it takes to fit within Discord's limits.
+/// Pages break between sections where possible, and between lines otherwise. `decorate` is
+/// applied to every embed, and must keep its author name and footer within [`DECORATION_LENGTH`].
+/// If the body can't be split sensibly, a single message is returned with the whole body attached
+/// as `file_name` instead.
+pub fn layout(
+    title: &str,
+    body: &str,
+    file_name: &str,
+    decorate: impl Fn(CreateEmbed) -> CreateEmbed,
+) -> Vec<CreateMessage> {
+    let title = truncate(title, TITLE_LIMIT - " (cont.)".len());
+    let Some(pages) = paginate(body, DESCRIPTION_LIMIT) else {
+        return attach(&title, body, file_name, decorate);
+    };
+
+    let mut messages = vec![];
+    let mut embeds = vec![];
+    let mut length = 0;
+    for (i, page) in pages.iter().enumerate() {
+        let page_title = if i == 0 {
+            title.clone()
+        } else {
+            format!("{} (cont.)", title)
+        };
+        let page_length = page_title.chars().count() + page.chars().count() + DECORATION_LENGTH;
+        if embeds.len() == EMBEDS_PER_MESSAGE || length + page_length > MESSAGE_LIMIT {
+            messages.push(CreateMessage::new().embeds(std::mem::take(&mut embeds)));
+            length = 0;
+        }
+
+        embeds.push(decorate(
+            CreateEmbed::default().title(page_title).description(page),
+        ));
+        length += page_length;
+    }
+    if !embeds.is_empty() {
+        messages.push(CreateMessage::new().embeds(embeds));
+    }
+
+    if messages.len() > MAX_MESSAGES {
+        debug!(
+            "{} would take {} messages, attaching it instead",
+            title,
+            messages.len()
+        );
+        return attach(&title, body, file_name, decorate);
+    }
+    messages
+}
+
+/// Splits `body` into pages of at most `limit` characters. Returns `None` if a single line is
+/// longer than `limit`.
+fn paginate(body: &str, limit: usize) -> Option<Vec<String>> {
+    let mut pages = vec![];
+    let mut page = String::new();
+    let mut page_length = 0;
+
+    for section in sections(body) {
+        let section_length = section.chars().count();
+        if page_length + section_length <= limit {
+            page.push_str(&section);
+            page_length += section_length;
+            continue;
+        }
+
+        // The section doesn't fit on this page, so it starts a new one. If it doesn't fit on a
+        // page of its own either, it is split between its lines.
+        for line in section.split_inclusive('\n') {
+            let line_length = line.chars().count();
+            if line_length > limit {
+                return None;
+            }
+            if page_length + line_length > limit || (page_length > 0 && line.starts_with('#')) {
+                pages.push(std::mem::take(&mut page));
+                page_length = 0;
+            }
+            page.push_str(line);
+            page_length += line_length;
+        }
+    }
+    if !page.trim().is_empty() {
+        pages.push(page);
+    }
+
+    Some(pages)
+}
+
+/// Splits `body` before every markdown heading, keeping the line breaks.
+fn sections(body: &str) -> Vec<String> {
+    let mut sections: Vec<String> = vec![];
+    for line in body.split_inclusive('\n') {
+        match sections.last_mut() {
+            Some(section) if !line.starts_with('#') => section.push_str(line),
+            _ => sections.push(line.to_string()),
+        }
+    }
+
+    sections
+}
+
+fn attach(
+    title: &str,
+    body: &str,
+    file_name: &str,
+    decorate: impl Fn(CreateEmbed) -> CreateEmbed,
+) -> Vec<CreateMessage> {
+    let embed = decorate(
+        CreateEmbed::default()
+            .title(title)
+            .description("The report is too long to show here, see the attached file."),
+    );
+    let content = format!("# {}\n\n{}", title, body);
+
+    vec![CreateMessage::new()
+        .embed(embed)
+        .add_file(CreateAttachment::bytes(content, file_name))]
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::CreateEmbedFooter;
+
+    use super::*;
+
+    fn embed_lengths(message: &CreateMessage) -> Vec<usize> {
+        let message = serde_json::to_value(message).unwrap();
+        message["embeds"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|embed| {
+                ["title", "description"]
+                    .iter()
+                    .filter_map(|field| embed[field].as_str())
+                    .chain(embed["author"]["name"].as_str())
+                    .chain(embed["footer"]["text"].as_str())
+                    .map(|text| text.chars().count())
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_sections_together() {
+        let body = "# One\nfirst\n# Two\nsecond\n";
+        assert_eq!(paginate(body, 100).unwrap(), vec![body.to_string()]);
+        assert_eq!(
+            paginate(body, 15).unwrap(),
+            vec!["# One\nfirst\n", "# Two\nsecond\n"]
+        );
+    }
+
+    #[test]
+    fn splits_long_sections_between_lines() {
+        let body = "# Title\naaaa\nbbbb\ncccc\n";
+        let pages = paginate(body, 13).unwrap();
+
+        assert_eq!(pages, vec!["# Title\naaaa\n", "bbbb\ncccc\n"]);
+        assert!(pages.iter().all(|page| page.chars().count() <= 13));
+    }
+
+    #[test]
+    fn gives_up_on_lines_longer_than_a_page() {
+        assert_eq!(paginate("short\nthis line is too long\n", 10), None);
+    }
+
+    #[test]
+    fn keeps_decorated_messages_within_the_limit() {
+        // Two pages whose titles and descriptions alone would just fit in one message.
+        let line = format!("- {}\n", "x".repeat(97));
+        let body = format!("# M\n{}", line.repeat(59));
+        let messages = layout("Report", &body, "report.md", |embed| {
+            embed
+                .author(crate::report::discord::author())
+                .footer(CreateEmbedFooter::new("x".repeat(50)))
+        });
+
+        assert!(messages.len() > 1);
+        for message in &messages {
+            let lengths = embed_lengths(message);
+            assert!(lengths.len() <= EMBEDS_PER_MESSAGE);
+            assert!(lengths.iter().sum::<usize>() <= MESSAGE_LIMIT);
+        }
+    }
+
+    #[test]
+    fn truncates_with_an_ellipsis() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a longer title", 8), "a longe…");
+    }
+}
@@ -0,0 +1,271 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use super::{DailyReport, GroupSummary, Leaderboard, ReportMember, ReportRenderer, ReportSection};
+
+/// Renders a report as a standalone markdown document.
+pub struct MarkdownRenderer;
+
+impl ReportRenderer for MarkdownRenderer {
+    type Output = String;
+
+    fn render(&self, report: &DailyReport) -> String {
+        let name = |_, member: &ReportMember| escape(&member.name);
+        format!(
+            "# {}\n\n{}{}{}{}",
+            report.title(),
+            body(report),
+            groups(report, name),
+            links(report, name),
+            flagged(report)
+        )
+    }
+}
+
+/// The sections of `report` in Discord-flavoured markdown, without a title.
+pub fn body(report: &DailyReport) -> String {
+    body_with(report, |_, member| escape(&member.name))
+}
+
+/// Like [`body`], but lists members the way `name` formats them in each section.
+pub fn body_with(
+    report: &DailyReport,
+    name: impl Fn(ReportSection, &ReportMember) -> String,
+) -> String {
+    if let Some(reason) = &report.skip_reason {
+        return format!(
+            "No updates were expected from {} ({}). Streaks are unchanged.",
+            report.window, reason
+        );
+    }
+
+    let mut desc = format!(
+        "Updates sent from {} were counted.\n# Leaderboard Updates\n",
+        report.window
+    );
+    let leader_name = |member: &ReportMember| name(ReportSection::Leaderboard, member);
+    desc.push_str(&format_section(
+        "All Time High",
+        &report.all_time_highs,
+        leader_name,
+    ));
+    desc.push_str(&format_section(
+        "Current Highest Streak",
+        &report.leaders,
+        leader_name,
+    ));
+
+    if !report.record_breakers.is_empty() {
+        desc.push_str("## New Personal Records\n");
+        for member in &report.record_breakers {
+            desc.push_str(&format!(
+                "- {} - {}\n",
+                name(ReportSection::RecordBreakers, member),
+                member.current_streak
+            ));
+        }
+    }
+
+    desc.push_str("# Missed Updates\n");
+    if report.missed.is_empty() {
+        desc.push_str("Everyone sent their update yesterday!\n");
+    }
+    for member in &report.missed {
+        let status = match member.current_streak {
+            0 => ":x:",
+            -1 => ":x::x:",
+            _ => ":headstone:",
+        };
+        desc.push_str(&format!(
+            "- {} | {}\n",
+            name(ReportSection::Missed, member),
+            status
+        ));
+    }
+
+    if !report.late.is_empty() {
+        desc.push_str("# Late Updates\n");
+        for member in &report.late {
+            desc.push_str(&format!("- {}\n", name(ReportSection::Late, member)));
+        }
+    }
+    if !report.made_up.is_empty() {
+        desc.push_str("# Make-up Updates\n");
+        for made_up in &report.made_up {
+            desc.push_str(&format!(
+                "- {} | restored {}\n",
+                name(ReportSection::MadeUp, &made_up.member),
+                made_up.date
+            ));
+        }
+    }
+    if !report.frozen.is_empty() {
+        desc.push_str("# Frozen Streaks\n");
+        for frozen in &report.frozen {
+            desc.push_str(&format!(
+                "- {} | :ice_cube: {} token(s) left\n",
+                name(ReportSection::Frozen, &frozen.member),
+                frozen.tokens_left
+            ));
+        }
+    }
+    if !report.overrides.is_empty() {
+        desc.push_str("# Mentor Overrides\n");
+        for o in &report.overrides {
+            let verdict = if o.accepted { "counted" } else { "rejected" };
+            desc.push_str(&format!(
+                "- [{}'s message]({}) was {} by {}\n",
+                escape(&o.author),
+                o.link,
+                verdict,
+                escape(&o.mentor)
+            ));
+        }
+    }
+    if let Some(exempt) = &report.exempt {
+        desc.push_str(&format!(
+            "# Excused\nUpdates were optional ({}), so {} members without one kept their streaks.\n",
+            exempt.reason, exempt.count
+        ));
+    }
+
+    desc
+}
+
+/// Lists the updates flagged as suspiciously similar to an earlier one, which only mentors should
+/// see, or nothing if none were flagged.
+pub fn flagged(report: &DailyReport) -> String {
+    if report.flagged.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("# Suspiciously Similar Updates\n");
+    for f in &report.flagged {
+        let counted = if f.counted { "" } else { " (not counted)" };
+        section.push_str(&format!(
+            "- [{}'s update]({}) is {:.0}% similar to [{}]({}){}\n",
+            escape(&f.author),
+            f.link,
+            f.similarity * 100.0,
+            f.source,
+            f.source_link,
+            counted
+        ));
+    }
+
+    section
+}
+
+/// Breaks the report down by Root group, or nothing if every member is in the same group.
+pub fn groups(
+    report: &DailyReport,
+    name: impl Fn(ReportSection, &ReportMember) -> String,
+) -> String {
+    if report.groups.len() < 2 {
+        return String::new();
+    }
+
+    let mut section = String::from("# By Group\n");
+    for group in &report.groups {
+        section.push_str(&group_section(group, &name));
+    }
+
+    section
+}
+
+/// The submission rate of `group` and the members who didn't send an update, who are listed as
+/// in [`ReportSection::Missed`].
+pub fn group_section(
+    group: &GroupSummary,
+    name: impl Fn(ReportSection, &ReportMember) -> String,
+) -> String {
+    let mut section = format!(
+        "## {} - {:.0}% ({}/{})\n",
+        group.label(),
+        group.rate() * 100.0,
+        group.sent,
+        group.expected
+    );
+    if group.missing.is_empty() {
+        section.push_str("Everyone sent their update!\n");
+    }
+    for member in &group.missing {
+        section.push_str(&format!("- {}\n", name(ReportSection::Missed, member)));
+    }
+
+    section
+}
+
+/// Lists a jump link to the update that counted for each member, or nothing if no updates were
+/// sent.
+pub fn links(
+    report: &DailyReport,
+    name: impl Fn(ReportSection, &ReportMember) -> String,
+) -> String {
+    if report.updates.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("# Accepted Updates\n");
+    for update in &report.updates {
+        let late = if update.late { " (late)" } else { "" };
+        section.push_str(&format!(
+            "- {} | [message]({}){}\n",
+            name(ReportSection::Updates, &update.member),
+            update.link,
+            late
+        ));
+    }
+
+    section
+}
+
+fn format_section(
+    title: &str,
+    leaderboard: &Leaderboard,
+    name: impl Fn(&ReportMember) -> String,
+) -> String {
+    if leaderboard.members.len() > 5 {
+        format!(
+            "## {} - {}\nMore than five members hold the record! Use `/leaderboard` to see them.\n",
+            title, leaderboard.streak
+        )
+    } else {
+        let mut section = format!("## {} - {}\n", title, leaderboard.streak);
+        for member in &leaderboard.members {
+            section.push_str(&format!("- {}\n", name(member)));
+        }
+        section
+    }
+}
+
+/// Escapes the characters that Discord's markdown would otherwise interpret, so that names show
+/// up as they are.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '~' | '`' | '|' | '>' | '#' | '[' | ']' | '-'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
@@ -0,0 +1,362 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+/// Renders reports as Discord embeds.
+pub mod discord;
+/// Renders reports as JSON, for exports.
+pub mod json;
+/// Splits long reports over as many embeds and messages as Discord's limits require.
+pub mod layout;
+/// Renders reports as markdown documents.
+pub mod markdown;
+/// Renders reports as plain text tables, for terminals.
+pub mod terminal;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use crate::storage::{history::Outcome, overrides::Verdict, runs::Run};
+
+/// Turns a [`DailyReport`] into something that can be shown or sent somewhere.
+pub trait ReportRenderer {
+    type Output;
+
+    fn render(&self, report: &DailyReport) -> Self::Output;
+}
+
+/// Everything a status update report shows, independent of how it is shown.
+#[derive(Clone, Debug, Serialize)]
+pub struct DailyReport {
+    pub profile: String,
+    pub date: NaiveDate,
+    /// The collection window the updates were counted from.
+    pub window: String,
+    /// Set if the calendar skipped the day, in which case every other section is empty.
+    pub skip_reason: Option<String>,
+    /// Members with the highest current streak.
+    pub leaders: Leaderboard,
+    /// Members with the highest max streak.
+    pub all_time_highs: Leaderboard,
+    /// Members whose current streak equals their max streak.
+    pub record_breakers: Vec<ReportMember>,
+    pub missed: Vec<ReportMember>,
+    /// Set if updates were optional on the day.
+    pub exempt: Option<Exemption>,
+    pub late: Vec<ReportMember>,
+    pub made_up: Vec<MadeUp>,
+    pub frozen: Vec<Frozen>,
+    /// The update that counted for each member who sent one.
+    pub updates: Vec<AcceptedUpdate>,
+    /// How each Root group did, ordered by group.
+    pub groups: Vec<GroupSummary>,
+    /// Messages mentors counted or rejected regardless of the rules.
+    pub overrides: Vec<ReportOverride>,
+    /// Updates that were suspiciously similar to an earlier one. Only mentors should see these.
+    pub flagged: Vec<FlaggedUpdate>,
+}
+
+/// The sections of a report that list members.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportSection {
+    /// Both the current and the all time leaderboard.
+    Leaderboard,
+    RecordBreakers,
+    Missed,
+    Late,
+    MadeUp,
+    Frozen,
+    /// The links to the accepted updates.
+    Updates,
+}
+
+impl ReportSection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportSection::Leaderboard => "Leaderboard",
+            ReportSection::RecordBreakers => "New personal records",
+            ReportSection::Missed => "Missed",
+            ReportSection::Late => "Late",
+            ReportSection::MadeUp => "Made up",
+            ReportSection::Frozen => "Frozen",
+            ReportSection::Updates => "Sent",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Leaderboard {
+    pub streak: i32,
+    pub members: Vec<ReportMember>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReportMember {
+    pub name: String,
+    pub discord_id: String,
+    pub current_streak: i32,
+    pub max_streak: i32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Exemption {
+    pub reason: String,
+    /// The number of members without an update who kept their streaks.
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MadeUp {
+    pub member: ReportMember,
+    /// The missed day that was restored.
+    pub date: NaiveDate,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AcceptedUpdate {
+    pub member: ReportMember,
+    /// A jump link to the message.
+    pub link: String,
+    pub late: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FlaggedUpdate {
+    pub author: String,
+    /// A jump link to the flagged update.
+    pub link: String,
+    /// Describes the earlier update, such as "Alice's update".
+    pub source: String,
+    /// A jump link to the earlier update.
+    pub source_link: String,
+    /// Between 0 and 1.
+    pub similarity: f64,
+    /// Whether the flagged update is the one that counted for its author.
+    pub counted: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReportOverride {
+    pub author: String,
+    /// A jump link to the message.
+    pub link: String,
+    pub accepted: bool,
+    pub mentor: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GroupSummary {
+    /// The Root group, or `None` for members without one.
+    pub group: Option<i32>,
+    /// Members who sent an update, late or not.
+    pub sent: usize,
+    /// Members who were expected to send one, which leaves out excused members.
+    pub expected: usize,
+    /// Members who were expected to send an update and didn't, including frozen ones.
+    pub missing: Vec<ReportMember>,
+}
+
+impl GroupSummary {
+    pub fn label(&self) -> String {
+        self.group
+            .map_or_else(|| "No group".to_string(), |id| format!("Group {}", id))
+    }
+
+    /// The share of expected members who sent an update, between 0 and 1.
+    pub fn rate(&self) -> f64 {
+        self.sent as f64 / self.expected.max(1) as f64
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Frozen {
+    pub member: ReportMember,
+    pub tokens_left: u32,
+}
+
+impl DailyReport {
+    /// Builds the report of an applied `run`, showing the streaks as they are after the run.
+    pub fn from_run(run: &Run) -> Self {
+        let mut report = Self {
+            profile: run.profile.clone(),
+            date: run.date,
+            window: run.window.clone(),
+            skip_reason: run.skip_reason.clone(),
+            leaders: Leaderboard::default(),
+            all_time_highs: Leaderboard::default(),
+            record_breakers: vec![],
+            missed: vec![],
+            exempt: None,
+            late: vec![],
+            made_up: vec![],
+            frozen: vec![],
+            updates: vec![],
+            groups: vec![],
+            overrides: run
+                .overrides
+                .iter()
+                .map(|o| ReportOverride {
+                    author: o.update.author_name.clone(),
+                    link: o.update.link(),
+                    accepted: o.verdict == Verdict::Accept,
+                    mentor: o.mentor_name.clone(),
+                })
+                .collect(),
+            flagged: vec![],
+        };
+        let mut excused = 0;
+        let mut groups: BTreeMap<Option<i32>, GroupSummary> = BTreeMap::new();
+
+        for planned in &run.members {
+            let streak = planned.streak.as_ref();
+            let member = ReportMember {
+                name: planned.member.name.clone(),
+                discord_id: planned.member.discord_id.clone(),
+                current_streak: streak.map_or(0, |s| s.current_streak),
+                max_streak: streak.map_or(0, |s| s.max_streak),
+            };
+            if let Some(link) = &planned.message_link {
+                report.updates.push(AcceptedUpdate {
+                    member: member.clone(),
+                    link: link.clone(),
+                    late: planned.outcome == Outcome::Late,
+                });
+            }
+            let group_id = planned.member.group_id;
+            let group = groups.entry(group_id).or_insert_with(|| GroupSummary {
+                group: group_id,
+                sent: 0,
+                expected: 0,
+                missing: vec![],
+            });
+            match planned.outcome {
+                Outcome::Accepted | Outcome::Late => group.sent += 1,
+                Outcome::Missed | Outcome::Frozen => group.missing.push(member.clone()),
+                Outcome::Excused | Outcome::MadeUp => {}
+            }
+            if planned.outcome != Outcome::Excused {
+                group.expected += 1;
+            }
+
+            if let Some(restored) = &planned.makeup {
+                report.made_up.push(MadeUp {
+                    member: member.clone(),
+                    date: restored.date,
+                });
+            }
+
+            match planned.outcome {
+                Outcome::Accepted | Outcome::Late => {
+                    if planned.outcome == Outcome::Late {
+                        report.late.push(member.clone());
+                    }
+                    if streak.is_none() {
+                        continue;
+                    }
+
+                    if member.current_streak >= report.leaders.streak {
+                        report.leaders.streak = member.current_streak;
+                        report.leaders.members.push(member.clone());
+                    }
+                    if member.current_streak == member.max_streak {
+                        report.record_breakers.push(member.clone());
+                    }
+                    if member.max_streak >= report.all_time_highs.streak {
+                        report.all_time_highs.streak = member.max_streak;
+                        report.all_time_highs.members.push(member);
+                    }
+                }
+                Outcome::Excused => excused += 1,
+                Outcome::Frozen => report.frozen.push(Frozen {
+                    member,
+                    tokens_left: planned.tokens_left.unwrap_or_default(),
+                }),
+                Outcome::Missed => report.missed.push(member),
+                Outcome::MadeUp => {}
+            }
+        }
+
+        // Members are added as the highest streak grows, so earlier ones may have been overtaken.
+        let (leaders, all_time_highs) = (report.leaders.streak, report.all_time_highs.streak);
+        report
+            .leaders
+            .members
+            .retain(|m| m.current_streak == leaders);
+        report
+            .all_time_highs
+            .members
+            .retain(|m| m.max_streak == all_time_highs);
+        report.groups = groups.into_values().collect();
+        report.flagged = run
+            .similar
+            .iter()
+            .map(|s| {
+                let source = if s.similar_to.author_id == s.update.author_id {
+                    format!(
+                        "their own update on {}",
+                        s.similar_to.timestamp.date_naive()
+                    )
+                } else {
+                    format!("{}'s update", s.similar_to.author_name)
+                };
+                let link = s.update.link();
+                FlaggedUpdate {
+                    author: s.update.author_name.clone(),
+                    counted: report.updates.iter().any(|u| u.link == link),
+                    link,
+                    source,
+                    source_link: s.similar_to.link(),
+                    similarity: s.similarity,
+                }
+            })
+            .collect();
+        report.exempt = run.optional_reason.as_ref().map(|reason| Exemption {
+            reason: reason.clone(),
+            count: excused,
+        });
+        report
+    }
+
+    /// Returns the members listed in `section`, each of them once.
+    pub fn members_in(&self, section: ReportSection) -> Vec<&ReportMember> {
+        let mut members: Vec<&ReportMember> = match section {
+            ReportSection::Leaderboard => self
+                .all_time_highs
+                .members
+                .iter()
+                .chain(&self.leaders.members)
+                .collect(),
+            ReportSection::RecordBreakers => self.record_breakers.iter().collect(),
+            ReportSection::Missed => self.missed.iter().collect(),
+            ReportSection::Late => self.late.iter().collect(),
+            ReportSection::MadeUp => self.made_up.iter().map(|m| &m.member).collect(),
+            ReportSection::Frozen => self.frozen.iter().map(|f| &f.member).collect(),
+            ReportSection::Updates => self.updates.iter().map(|u| &u.member).collect(),
+        };
+        let mut seen = std::collections::HashSet::new();
+        members.retain(|m| seen.insert(m.discord_id.as_str()));
+
+        members
+    }
+
+    /// The title shared by every renderer.
+    pub fn title(&self) -> String {
+        format!("Status Update Report - {}", self.date)
+    }
+}
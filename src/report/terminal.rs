@@ -0,0 +1,120 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use super::{DailyReport, ReportMember, ReportRenderer};
+
+/// Renders a report as a plain text table with a row for every member that appears in it.
+pub struct TerminalRenderer;
+
+impl ReportRenderer for TerminalRenderer {
+    type Output = String;
+
+    fn render(&self, report: &DailyReport) -> String {
+        let mut output = format!("{}\n{}\n", report.title(), report.window);
+        if let Some(reason) = &report.skip_reason {
+            output.push_str(&format!("Skipped: {}\n", reason));
+            return output;
+        }
+        if let Some(exempt) = &report.exempt {
+            output.push_str(&format!(
+                "Optional: {} ({} excused)\n",
+                exempt.reason, exempt.count
+            ));
+        }
+
+        for f in &report.flagged {
+            output.push_str(&format!(
+                "Similar: {} is {:.0}% similar to {} ({})\n",
+                f.author,
+                f.similarity * 100.0,
+                f.source,
+                f.link
+            ));
+        }
+        for o in &report.overrides {
+            let verdict = if o.accepted { "Counted" } else { "Rejected" };
+            output.push_str(&format!(
+                "{} by {}: {} ({})\n",
+                verdict, o.mentor, o.author, o.link
+            ));
+        }
+        if report.groups.len() > 1 {
+            for group in &report.groups {
+                output.push_str(&format!(
+                    "{}: {:.0}% ({}/{})\n",
+                    group.label(),
+                    group.rate() * 100.0,
+                    group.sent,
+                    group.expected
+                ));
+            }
+        }
+
+        let mut rows: Vec<(&str, &ReportMember, String)> = vec![];
+        for member in &report.all_time_highs.members {
+            rows.push(("All time high", member, String::new()));
+        }
+        for member in &report.leaders.members {
+            rows.push(("Highest streak", member, String::new()));
+        }
+        for member in &report.record_breakers {
+            rows.push(("Personal record", member, String::new()));
+        }
+        for member in &report.late {
+            rows.push(("Late", member, String::new()));
+        }
+        for made_up in &report.made_up {
+            rows.push(("Made up", &made_up.member, made_up.date.to_string()));
+        }
+        for frozen in &report.frozen {
+            let note = format!("{} token(s) left", frozen.tokens_left);
+            rows.push(("Frozen", &frozen.member, note));
+        }
+        for member in &report.missed {
+            rows.push(("Missed", member, String::new()));
+        }
+
+        let name_width = rows
+            .iter()
+            .map(|(_, member, _)| member.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Member".len());
+        output.push_str(&format!(
+            "\n{:<15} | {:<name_width$} | {:>7} | {:>7} | Note\n",
+            "Section", "Member", "Current", "Max"
+        ));
+        output.push_str(&format!(
+            "{}-+-{}-+-{}-+-{}-+-{}\n",
+            "-".repeat(15),
+            "-".repeat(name_width),
+            "-".repeat(7),
+            "-".repeat(7),
+            "-".repeat(4)
+        ));
+        for (section, member, note) in rows {
+            let row = format!(
+                "{:<15} | {:<name_width$} | {:>7} | {:>7} | {}",
+                section, member.name, member.current_streak, member.max_streak, note
+            );
+            output.push_str(row.trim_end());
+            output.push('\n');
+        }
+
+        output
+    }
+}
@@ -16,20 +16,23 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 use crate::tasks::{get_tasks, Task};
+use crate::updates::profile::Profile;
 
 use serenity::client::Context as SerenityContext;
 use tokio::spawn;
 use tracing::{debug, error, trace};
 
 /// Spawns a sleepy thread for each [`Task`].
-pub async fn run_scheduler(ctx: SerenityContext) {
+pub async fn run_scheduler(ctx: SerenityContext, profiles: &[Profile]) -> anyhow::Result<()> {
     trace!("Running scheduler");
-    let tasks = get_tasks();
+    let tasks = get_tasks(profiles);
 
     for task in tasks {
         debug!("Spawing task {}", task.name());
         spawn(schedule_task(ctx.clone(), task));
     }
+
+    Ok(())
 }
 
 /// Runs the function [`Task::run`] and goes back to sleep until it's time to run again.
@@ -40,7 +43,6 @@ async fn schedule_task(ctx: SerenityContext, task: Box<dyn Task>) {
         tokio::time::sleep(next_run_in).await;
 
         debug!("Running task {}", task.name());
-        tokio::time::sleep(next_run_in).await;
         if let Err(e) = task.run(ctx.clone()).await {
             error!("Could not run task {}, error {}", task.name(), e);
         }
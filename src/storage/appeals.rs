@@ -0,0 +1,116 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+use super::{load, modify};
+
+const FILE_NAME: &str = "appeals.json";
+
+/// A member's request to count an update that the report of `date` missed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Appeal {
+    pub id: u64,
+    pub profile: String,
+    pub date: NaiveDate,
+    pub discord_id: String,
+    pub name: String,
+    /// A link to the update the member says should have counted.
+    pub message_link: String,
+    pub status: AppealStatus,
+    #[serde(default)]
+    pub decided_by: Option<UserId>,
+    /// The streak that approving the appeal restores in Root, saved before it is restored so that
+    /// an approval that failed halfway can be retried without counting any day twice.
+    #[serde(default)]
+    pub target_streak: Option<i32>,
+    /// Set once an approval was fully applied.
+    #[serde(default)]
+    pub applied: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppealStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+/// Records a new pending appeal and returns it with its ID.
+pub fn create(
+    profile: &str,
+    date: NaiveDate,
+    discord_id: &str,
+    name: &str,
+    message_link: &str,
+) -> anyhow::Result<Appeal> {
+    modify(FILE_NAME, |appeals: &mut Vec<Appeal>| {
+        let appeal = Appeal {
+            id: appeals.iter().map(|a| a.id).max().unwrap_or(0) + 1,
+            profile: profile.to_string(),
+            date,
+            discord_id: discord_id.to_string(),
+            name: name.to_string(),
+            message_link: message_link.to_string(),
+            status: AppealStatus::Pending,
+            decided_by: None,
+            target_streak: None,
+            applied: false,
+        };
+        appeals.push(appeal.clone());
+        appeal
+    })
+}
+
+pub fn get(id: u64) -> anyhow::Result<Option<Appeal>> {
+    let appeals: Vec<Appeal> = load(FILE_NAME)?;
+
+    Ok(appeals.into_iter().find(|a| a.id == id))
+}
+
+/// Returns the member's appeal of the report of `date`, if they already made one.
+pub fn find(profile: &str, date: NaiveDate, discord_id: &str) -> anyhow::Result<Option<Appeal>> {
+    let appeals: Vec<Appeal> = load(FILE_NAME)?;
+
+    Ok(appeals
+        .into_iter()
+        .find(|a| a.profile == profile && a.date == date && a.discord_id == discord_id))
+}
+
+/// Marks the appeal as decided by `mentor`. Returns `None` if it doesn't exist or was already
+/// decided, so that it is never decided twice.
+pub fn decide(id: u64, status: AppealStatus, mentor: UserId) -> anyhow::Result<Option<Appeal>> {
+    modify(FILE_NAME, |appeals: &mut Vec<Appeal>| {
+        let appeal = appeals
+            .iter_mut()
+            .find(|a| a.id == id && a.status == AppealStatus::Pending)?;
+        appeal.status = status;
+        appeal.decided_by = Some(mentor);
+        Some(appeal.clone())
+    })
+}
+
+/// Saves `appeal`, replacing its earlier state.
+pub fn save(appeal: &Appeal) -> anyhow::Result<()> {
+    modify(FILE_NAME, |appeals: &mut Vec<Appeal>| {
+        appeals.retain(|a| a.id != appeal.id);
+        appeals.push(appeal.clone());
+    })
+}
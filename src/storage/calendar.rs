@@ -0,0 +1,69 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{load, modify};
+
+const FILE_NAME: &str = "calendar.json";
+
+/// How a day without regular expectations is evaluated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, poise::ChoiceParameter)]
+#[serde(rename_all = "snake_case")]
+pub enum DayMode {
+    /// The day is not evaluated at all.
+    Skip,
+    /// Updates count for members who send them, but nobody is penalized for not sending one.
+    Optional,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub mode: DayMode,
+    pub reason: String,
+}
+
+/// Saves `holidays`, replacing any existing holidays on the same dates.
+pub fn add(holidays: Vec<Holiday>) -> anyhow::Result<()> {
+    modify(FILE_NAME, |existing: &mut Vec<Holiday>| {
+        existing.retain(|e| !holidays.iter().any(|h| h.date == e.date));
+        existing.extend(holidays);
+        existing.sort_by_key(|h| h.date);
+    })
+}
+
+/// Removes every holiday from `start` to `end`, both inclusive. Returns how many were removed.
+pub fn remove(start: NaiveDate, end: NaiveDate) -> anyhow::Result<usize> {
+    modify(FILE_NAME, |existing: &mut Vec<Holiday>| {
+        let len = existing.len();
+        existing.retain(|h| h.date < start || h.date > end);
+        len - existing.len()
+    })
+}
+
+/// Returns every holiday on or after `since`, earliest first.
+pub fn since(since: NaiveDate) -> anyhow::Result<Vec<Holiday>> {
+    let holidays: Vec<Holiday> = load(FILE_NAME)?;
+    Ok(holidays.into_iter().filter(|h| h.date >= since).collect())
+}
+
+pub fn get(date: NaiveDate) -> anyhow::Result<Option<Holiday>> {
+    let holidays: Vec<Holiday> = load(FILE_NAME)?;
+    Ok(holidays.into_iter().find(|h| h.date == date))
+}
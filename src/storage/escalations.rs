@@ -0,0 +1,62 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{load, modify};
+
+const FILE_NAME: &str = "escalations.json";
+
+/// An escalation step that fired for a member.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Escalation {
+    pub profile: String,
+    pub discord_id: String,
+    /// The number of consecutive misses the step is configured for.
+    pub after: u32,
+    pub date: NaiveDate,
+    /// Set once the member sends an update again, which re-arms every step.
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+/// Returns the steps that fired for the member's current run of misses.
+pub fn active(profile: &str, discord_id: &str) -> anyhow::Result<Vec<Escalation>> {
+    let escalations: Vec<Escalation> = load(FILE_NAME)?;
+
+    Ok(escalations
+        .into_iter()
+        .filter(|e| !e.resolved && e.profile == profile && e.discord_id == discord_id)
+        .collect())
+}
+
+pub fn record(escalation: Escalation) -> anyhow::Result<()> {
+    modify(FILE_NAME, |escalations: &mut Vec<Escalation>| {
+        escalations.push(escalation)
+    })
+}
+
+/// Marks the member's active steps as resolved.
+pub fn resolve(profile: &str, discord_id: &str) -> anyhow::Result<()> {
+    modify(FILE_NAME, |escalations: &mut Vec<Escalation>| {
+        escalations
+            .iter_mut()
+            .filter(|e| !e.resolved && e.profile == profile && e.discord_id == discord_id)
+            .for_each(|e| e.resolved = true);
+    })
+}
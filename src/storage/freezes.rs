@@ -0,0 +1,78 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use super::{load, modify};
+use crate::updates::profile::FreezePolicy;
+
+const FILE_NAME: &str = "freezes.json";
+
+/// A member's streak freeze tokens, keyed by their Discord ID.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FreezeBalance {
+    pub tokens: u32,
+    /// Consecutive updates sent since the last token was earned.
+    pub progress: u32,
+}
+
+pub fn get(discord_id: &str) -> anyhow::Result<FreezeBalance> {
+    let balances: HashMap<String, FreezeBalance> = load(FILE_NAME)?;
+    Ok(balances.get(discord_id).cloned().unwrap_or_default())
+}
+
+/// Counts an accepted update towards the next token. Returns whether a token was earned.
+pub fn on_accepted(discord_id: &str, policy: &FreezePolicy) -> anyhow::Result<bool> {
+    modify(
+        FILE_NAME,
+        |balances: &mut HashMap<String, FreezeBalance>| {
+            let balance = balances.entry(discord_id.to_string()).or_default();
+            balance.progress += 1;
+            if balance.progress < policy.every {
+                return false;
+            }
+
+            balance.progress = 0;
+            if balance.tokens < policy.cap {
+                balance.tokens += 1;
+                true
+            } else {
+                false
+            }
+        },
+    )
+}
+
+/// Breaks the chain of consecutive updates and spends a token if one is available. Returns the
+/// remaining tokens if one was spent.
+pub fn on_missed(discord_id: &str) -> anyhow::Result<Option<u32>> {
+    modify(
+        FILE_NAME,
+        |balances: &mut HashMap<String, FreezeBalance>| {
+            let balance = balances.entry(discord_id.to_string()).or_default();
+            balance.progress = 0;
+            if balance.tokens == 0 {
+                return None;
+            }
+
+            balance.tokens -= 1;
+            Some(balance.tokens)
+        },
+    )
+}
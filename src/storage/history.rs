@@ -0,0 +1,145 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{load, modify};
+
+const FILE_NAME: &str = "history.json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Accepted,
+    Missed,
+    /// No update was sent on a day that the calendar marked as optional.
+    Excused,
+    /// No update was sent, but a freeze token kept the streak alive.
+    Frozen,
+    /// The update was sent during the grace period after the window closed.
+    Late,
+    /// The day was missed, but a make-up update restored the streak later.
+    MadeUp,
+}
+
+/// The result of evaluating one member on one day.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyOutcome {
+    /// The name of the profile the member was evaluated under.
+    pub profile: String,
+    pub date: NaiveDate,
+    pub member_id: i32,
+    pub name: String,
+    pub discord_id: String,
+    pub group_id: Option<i32>,
+    pub outcome: Outcome,
+    /// A link to the update that was accepted, if any.
+    #[serde(default)]
+    pub message_link: Option<String>,
+    /// The member's current streak before the evaluation.
+    #[serde(default)]
+    pub streak_before: i32,
+    /// The member's current streak after the evaluation.
+    #[serde(alias = "streak")]
+    pub streak_after: i32,
+}
+
+/// Saves `outcome`, replacing any earlier outcome of the same member on the same day.
+pub fn record(outcome: DailyOutcome) -> anyhow::Result<()> {
+    modify(FILE_NAME, |history: &mut Vec<DailyOutcome>| {
+        history.retain(|o| {
+            !(o.profile == outcome.profile
+                && o.date == outcome.date
+                && o.member_id == outcome.member_id)
+        });
+        history.push(outcome);
+    })
+}
+
+/// A filter over the recorded [`DailyOutcome`]s. Every criterion that is left unset matches
+/// everything, so `Query::default().fetch()` returns the whole history.
+///
+/// ```ignore
+/// let missed = Query::default()
+///     .discord_id("1234")
+///     .since(semester_start)
+///     .outcome(Outcome::Missed)
+///     .count()?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    profile: Option<String>,
+    discord_id: Option<String>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    outcome: Option<Outcome>,
+}
+
+impl Query {
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    pub fn discord_id(mut self, discord_id: impl Into<String>) -> Self {
+        self.discord_id = Some(discord_id.into());
+        self
+    }
+
+    /// Only match outcomes on or after `date`.
+    pub fn since(mut self, date: NaiveDate) -> Self {
+        self.since = Some(date);
+        self
+    }
+
+    /// Only match outcomes on or before `date`.
+    pub fn until(mut self, date: NaiveDate) -> Self {
+        self.until = Some(date);
+        self
+    }
+
+    pub fn outcome(mut self, outcome: Outcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+
+    fn matches(&self, o: &DailyOutcome) -> bool {
+        self.profile.as_ref().is_none_or(|p| &o.profile == p)
+            && self
+                .discord_id
+                .as_ref()
+                .is_none_or(|id| &o.discord_id == id)
+            && self.since.is_none_or(|date| o.date >= date)
+            && self.until.is_none_or(|date| o.date <= date)
+            && self.outcome.is_none_or(|outcome| o.outcome == outcome)
+    }
+
+    /// Returns the matching outcomes, oldest first.
+    pub fn fetch(&self) -> anyhow::Result<Vec<DailyOutcome>> {
+        let history: Vec<DailyOutcome> = load(FILE_NAME)?;
+        let mut outcomes: Vec<DailyOutcome> =
+            history.into_iter().filter(|o| self.matches(o)).collect();
+        outcomes.sort_by_key(|o| o.date);
+
+        Ok(outcomes)
+    }
+
+    pub fn count(&self) -> anyhow::Result<usize> {
+        Ok(self.fetch()?.len())
+    }
+}
@@ -0,0 +1,92 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+pub mod appeals;
+pub mod calendar;
+pub mod escalations;
+pub mod freezes;
+pub mod history;
+pub mod overrides;
+pub mod preferences;
+pub mod runs;
+pub mod updates;
+
+use anyhow::Context as _;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::trace;
+
+use std::{path::PathBuf, sync::Mutex};
+
+/// Serializes access to the files in [`data_dir`] so that the event handler and the scheduled
+/// tasks never interleave a read-modify-write of the same file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Returns the directory that amD persists its local state in, creating it if necessary.
+fn data_dir() -> anyhow::Result<PathBuf> {
+    let dir = std::env::var("DATA_DIR").context("DATA_DIR was not found in the ENV")?;
+    let dir = PathBuf::from(dir);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create data directory {}", dir.display()))?;
+
+    Ok(dir)
+}
+
+/// Reads `file_name` from [`data_dir`], or returns `T::default()` if it does not exist yet.
+fn read<T: DeserializeOwned + Default>(file_name: &str) -> anyhow::Result<T> {
+    let path = data_dir()?.join(file_name);
+    trace!("Reading {}", path.display());
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Writes `value` to `file_name` in [`data_dir`]. The file is replaced atomically so that a crash
+/// mid-write never leaves a truncated file behind.
+fn write<T: Serialize>(file_name: &str, value: &T) -> anyhow::Result<()> {
+    let path = data_dir()?.join(file_name);
+    trace!("Writing {}", path.display());
+    let tmp_path = path.with_extension("tmp");
+    let content = serde_json::to_string_pretty(value).context("Failed to serialize value")?;
+    std::fs::write(&tmp_path, content)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, &path)
+        .with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Returns a copy of the contents of `file_name`.
+pub(crate) fn load<T: DeserializeOwned + Default>(file_name: &str) -> anyhow::Result<T> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    read(file_name)
+}
+
+/// Loads `file_name`, applies `f` to it and writes the result back, all under [`LOCK`].
+pub(crate) fn modify<T, R, F>(file_name: &str, f: F) -> anyhow::Result<R>
+where
+    T: DeserializeOwned + Serialize + Default,
+    F: FnOnce(&mut T) -> R,
+{
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut value = read(file_name)?;
+    let result = f(&mut value);
+    write(file_name, &value)?;
+
+    Ok(result)
+}
@@ -0,0 +1,77 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::{Deserialize, Serialize};
+use serenity::all::{MessageId, Timestamp, UserId};
+
+use super::{load, modify, updates::RecordedUpdate};
+
+const FILE_NAME: &str = "overrides.json";
+
+/// A mentor's decision on whether a message counts as a status update, regardless of the rules.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Override {
+    /// The message the decision is about.
+    pub update: RecordedUpdate,
+    pub verdict: Verdict,
+    pub mentor_id: UserId,
+    pub mentor_name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Accept,
+    Reject,
+}
+
+/// Records `decision`, replacing any earlier decision on the same message.
+pub fn set(decision: Override) -> anyhow::Result<()> {
+    modify(FILE_NAME, |overrides: &mut Vec<Override>| {
+        overrides.retain(|o| o.update.message_id != decision.update.message_id);
+        overrides.push(decision);
+    })
+}
+
+/// Removes the decision on `message_id` if it is `verdict`, or any decision if `verdict` is
+/// `None`. Returns whether a decision was removed.
+pub fn remove(message_id: MessageId, verdict: Option<Verdict>) -> anyhow::Result<bool> {
+    let matches =
+        |o: &Override| o.update.message_id == message_id && verdict.is_none_or(|v| v == o.verdict);
+    // Called for every message deleted in an update channel, so the file is only rewritten when
+    // there is something to remove.
+    let overrides: Vec<Override> = load(FILE_NAME)?;
+    if !overrides.iter().any(matches) {
+        return Ok(false);
+    }
+
+    modify(FILE_NAME, |overrides: &mut Vec<Override>| {
+        let len = overrides.len();
+        overrides.retain(|o| !matches(o));
+        overrides.len() != len
+    })
+}
+
+/// Returns the decisions on messages posted in `[start, end)`.
+pub fn between(start: Timestamp, end: Timestamp) -> anyhow::Result<Vec<Override>> {
+    let overrides: Vec<Override> = load(FILE_NAME)?;
+
+    Ok(overrides
+        .into_iter()
+        .filter(|o| o.update.timestamp >= start && o.update.timestamp < end)
+        .collect())
+}
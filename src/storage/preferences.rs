@@ -0,0 +1,76 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+use std::collections::{HashMap, HashSet};
+
+use super::{load, modify};
+
+const FILE_NAME: &str = "preferences.json";
+
+/// Per-member settings that members can change themselves.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Preferences {
+    /// Whether to remind the member before the collection window closes.
+    #[serde(default = "enabled")]
+    pub reminders: bool,
+    /// Whether the member may be pinged by the report, in the sections that ping.
+    #[serde(default)]
+    pub pings: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            reminders: enabled(),
+            pings: false,
+        }
+    }
+}
+
+/// Returns the preferences of `user_id`, or the defaults if they never changed any.
+pub fn get(user_id: UserId) -> anyhow::Result<Preferences> {
+    let preferences: HashMap<UserId, Preferences> = load(FILE_NAME)?;
+    Ok(preferences.get(&user_id).cloned().unwrap_or_default())
+}
+
+/// Returns every member who opted in to being pinged by the report.
+pub fn opted_in_to_pings() -> anyhow::Result<HashSet<UserId>> {
+    let preferences: HashMap<UserId, Preferences> = load(FILE_NAME)?;
+    Ok(preferences
+        .into_iter()
+        .filter(|(_, p)| p.pings)
+        .map(|(user_id, _)| user_id)
+        .collect())
+}
+
+/// Applies `f` to the preferences of `user_id` and saves them.
+pub fn update<F>(user_id: UserId, f: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&mut Preferences),
+{
+    modify(
+        FILE_NAME,
+        |preferences: &mut HashMap<UserId, Preferences>| f(preferences.entry(user_id).or_default()),
+    )
+}
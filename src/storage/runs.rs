@@ -0,0 +1,208 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, MessageId};
+
+use super::{
+    history::{DailyOutcome, Outcome},
+    load, modify,
+    overrides::Override,
+    updates::RecordedUpdate,
+};
+use crate::{
+    graphql::models::{Member, Streak},
+    updates::similarity::SimilarUpdate,
+};
+
+const FILE_NAME: &str = "runs.json";
+
+/// Finished runs are kept for this many days after a newer run is saved.
+const RETENTION_DAYS: i64 = 30;
+/// A run that failed this many times is given up on, so that it doesn't keep being retried.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// A status update report of one profile on one day. A run is planned first, then its planned
+/// changes are applied and finally it is published. It is saved after every step, so that a run
+/// that failed can be resumed without repeating the steps that already succeeded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Run {
+    pub profile: String,
+    pub date: NaiveDate,
+    /// Unset while the run is only saved so that it is retried, and nothing was planned yet.
+    #[serde(default = "planned")]
+    pub planned: bool,
+    /// The collection window, as described in the report.
+    pub window: String,
+    /// Set if the calendar skips the day, in which case nothing else is planned.
+    pub skip_reason: Option<String>,
+    /// Set if updates were optional on the day.
+    pub optional_reason: Option<String>,
+    pub members: Vec<PlannedMember>,
+    /// Every update that was collected, including the ones that were rejected.
+    pub updates: Vec<RecordedUpdate>,
+    pub similar: Vec<SimilarUpdate>,
+    /// The mentors' decisions that were honored.
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+    pub digest_sent: bool,
+    pub report_sent: bool,
+    /// The first message of the report, which the thread of update links is started from.
+    #[serde(default)]
+    pub report_message: Option<MessageId>,
+    /// The thread of update links, once it was started.
+    #[serde(default)]
+    pub links_thread: Option<ChannelId>,
+    #[serde(default)]
+    pub links_sent: bool,
+    /// Groups whose section was posted to their own channel.
+    #[serde(default)]
+    pub groups_sent: Vec<Option<i32>>,
+    #[serde(default)]
+    pub breakdown_sent: bool,
+    pub escalated: bool,
+    /// How many messages of the step being published were sent.
+    #[serde(default)]
+    pub messages_sent: usize,
+    /// How many times resuming the run failed.
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+impl Run {
+    pub fn new(profile: String, date: NaiveDate, window: String) -> Self {
+        Self {
+            profile,
+            date,
+            planned: true,
+            window,
+            skip_reason: None,
+            optional_reason: None,
+            members: vec![],
+            updates: vec![],
+            similar: vec![],
+            overrides: vec![],
+            digest_sent: false,
+            report_sent: false,
+            report_message: None,
+            links_thread: None,
+            links_sent: false,
+            groups_sent: vec![],
+            breakdown_sent: false,
+            escalated: false,
+            messages_sent: 0,
+            attempts: 0,
+        }
+    }
+
+    /// A run that still has to be planned.
+    pub fn unplanned(profile: String, date: NaiveDate) -> Self {
+        Self {
+            planned: false,
+            ..Self::new(profile, date, String::new())
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.planned
+            && self.digest_sent
+            && self.report_sent
+            && self.links_sent
+            && self.breakdown_sent
+            && self.escalated
+    }
+
+    pub fn is_abandoned(&self) -> bool {
+        !self.is_finished() && self.attempts >= MAX_ATTEMPTS
+    }
+}
+
+/// Runs saved before unplanned runs existed were always planned.
+fn planned() -> bool {
+    true
+}
+
+/// What a run does for one member.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlannedMember {
+    /// The member as fetched from Root when the run was planned.
+    pub member: Member,
+    pub outcome: Outcome,
+    pub message_link: Option<String>,
+    /// The streak the outcome is applied to, after any make-up.
+    pub streak_before: i32,
+    /// The missed day that a make-up update restores, as it is recorded once restored.
+    pub makeup: Option<DailyOutcome>,
+    /// The freeze tokens left after one is spent, for [`Outcome::Frozen`].
+    pub tokens_left: Option<u32>,
+    pub stage: Stage,
+    /// The member's streak once it was updated in Root.
+    pub streak: Option<Streak>,
+}
+
+/// How far a [`PlannedMember`] has been applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Pending,
+    /// The streak was updated in Root, but nothing was recorded locally yet.
+    StreakUpdated,
+    /// The freeze tokens were updated, but the outcome wasn't recorded in the history yet.
+    FreezesUpdated,
+    Done,
+}
+
+pub fn get(profile: &str, date: NaiveDate) -> anyhow::Result<Option<Run>> {
+    let runs: Vec<Run> = load(FILE_NAME)?;
+    Ok(runs
+        .into_iter()
+        .find(|r| r.profile == profile && r.date == date))
+}
+
+/// Returns the most recent planned run of `profile`.
+pub fn latest(profile: &str) -> anyhow::Result<Option<Run>> {
+    let runs: Vec<Run> = load(FILE_NAME)?;
+    Ok(runs
+        .into_iter()
+        .filter(|r| r.profile == profile && r.planned)
+        .max_by_key(|r| r.date))
+}
+
+/// Returns the runs of `profile` that haven't finished and weren't given up on, oldest first.
+pub fn unfinished(profile: &str) -> anyhow::Result<Vec<Run>> {
+    let runs: Vec<Run> = load(FILE_NAME)?;
+    let mut unfinished: Vec<Run> = runs
+        .into_iter()
+        .filter(|r| r.profile == profile && !r.is_finished() && !r.is_abandoned())
+        .collect();
+    unfinished.sort_by_key(|r| r.date);
+
+    Ok(unfinished)
+}
+
+/// Saves `run`, replacing its earlier state, and forgets finished or abandoned runs that are too
+/// old to matter.
+pub fn save(run: &Run) -> anyhow::Result<()> {
+    modify(FILE_NAME, |runs: &mut Vec<Run>| {
+        let cutoff = run.date - chrono::Duration::days(RETENTION_DAYS);
+        let is_replaced = |r: &Run| r.profile == run.profile && r.date == run.date;
+        let is_expired = |r: &Run| (r.is_finished() || r.is_abandoned()) && r.date < cutoff;
+        runs.retain(|r| !is_replaced(r) && !is_expired(r));
+        runs.push(run.clone());
+    })
+}
@@ -0,0 +1,131 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId, Message, MessageId, Timestamp, UserId};
+
+use super::{load, modify};
+use crate::updates::parser::StatusUpdate;
+
+const FILE_NAME: &str = "updates.json";
+/// How long updates are kept. Long enough for the similarity check to look back on, while
+/// outcomes are kept for good in the history.
+const RETENTION_DAYS: i64 = 14;
+
+/// A status update that was accepted when it was posted, as recorded by the event handler.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedUpdate {
+    pub message_id: MessageId,
+    pub channel_id: ChannelId,
+    #[serde(default)]
+    pub guild_id: Option<GuildId>,
+    pub author_id: UserId,
+    pub author_name: String,
+    pub content: String,
+    pub timestamp: Timestamp,
+    /// The sections of the update, parsed from `content` when it was recorded.
+    #[serde(default)]
+    pub parsed: StatusUpdate,
+}
+
+impl From<&Message> for RecordedUpdate {
+    fn from(msg: &Message) -> Self {
+        Self {
+            message_id: msg.id,
+            channel_id: msg.channel_id,
+            guild_id: msg.guild_id,
+            author_id: msg.author.id,
+            author_name: msg.author.name.clone(),
+            content: msg.content.clone(),
+            timestamp: msg.timestamp,
+            parsed: StatusUpdate::default(),
+        }
+    }
+}
+
+impl RecordedUpdate {
+    /// Returns a link that jumps to the update.
+    pub fn link(&self) -> String {
+        self.message_id.link(self.channel_id, self.guild_id)
+    }
+}
+
+/// Records `update`, replacing any earlier record of the same message.
+pub fn record(update: RecordedUpdate) -> anyhow::Result<()> {
+    record_all(vec![update])
+}
+
+/// Records every update in `new`, replacing any earlier records of the same messages. Updates
+/// older than [`RETENTION_DAYS`] are dropped.
+pub fn record_all(new: Vec<RecordedUpdate>) -> anyhow::Result<()> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(RETENTION_DAYS);
+    // Backfilling mostly finds updates that were already recorded, so the file is only rewritten
+    // when something changes.
+    let updates: Vec<RecordedUpdate> = load(FILE_NAME)?;
+    let is_recorded = |n: &RecordedUpdate| {
+        updates
+            .iter()
+            .any(|u| u.message_id == n.message_id && u.content == n.content)
+    };
+    if new.iter().all(is_recorded) && updates.iter().all(|u| *u.timestamp >= cutoff) {
+        return Ok(());
+    }
+
+    modify(FILE_NAME, |updates: &mut Vec<RecordedUpdate>| {
+        updates.retain(|u| {
+            *u.timestamp >= cutoff && !new.iter().any(|n| n.message_id == u.message_id)
+        });
+        updates.extend(new);
+    })
+}
+
+/// Removes the record for `message_id`. Returns whether a record existed.
+pub fn remove(message_id: MessageId) -> anyhow::Result<bool> {
+    // Most messages in update channels were never recorded, so the file is only rewritten when
+    // there is something to remove.
+    let updates: Vec<RecordedUpdate> = load(FILE_NAME)?;
+    if !updates.iter().any(|u| u.message_id == message_id) {
+        return Ok(false);
+    }
+
+    modify(FILE_NAME, |updates: &mut Vec<RecordedUpdate>| {
+        let len = updates.len();
+        updates.retain(|u| u.message_id != message_id);
+        updates.len() != len
+    })
+}
+
+/// Returns every recorded update posted in `[start, end)`.
+pub fn between(start: Timestamp, end: Timestamp) -> anyhow::Result<Vec<RecordedUpdate>> {
+    let updates: Vec<RecordedUpdate> = load(FILE_NAME)?;
+
+    Ok(updates
+        .into_iter()
+        .filter(|u| u.timestamp >= start && u.timestamp < end)
+        .collect())
+}
+
+/// Returns the channel of the most recent update recorded for `author_id`.
+pub fn latest_channel(author_id: UserId) -> anyhow::Result<Option<ChannelId>> {
+    let updates: Vec<RecordedUpdate> = load(FILE_NAME)?;
+
+    Ok(updates
+        .iter()
+        .filter(|u| u.author_id == author_id)
+        .max_by_key(|u| u.timestamp)
+        .map(|u| u.channel_id))
+}
@@ -0,0 +1,281 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serenity::all::{Context, Timestamp};
+use serenity::async_trait;
+use tracing::{debug, trace};
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::Task;
+use crate::{
+    report::{discord, layout},
+    storage::history::{DailyOutcome, Outcome, Query},
+    updates::profile::Profile,
+};
+
+/// Members who sent fewer than this fraction of their updates are listed as chronic misses.
+const CHRONIC_MISS_RATE: f64 = 0.5;
+const MAX_LISTED: usize = 5;
+const DIGEST_TIME: NaiveTime = NaiveTime::from_hms_opt(10, 0, 0).expect("Valid time");
+
+#[derive(Clone, Copy, Debug)]
+pub enum Period {
+    Weekly,
+    Monthly,
+}
+
+/// Summarizes the daily outcomes recorded by [`super::status_update::StatusUpdateCheck`]
+/// over a week or a month.
+pub struct Digest {
+    name: String,
+    profile: Profile,
+    period: Period,
+}
+
+impl Digest {
+    pub fn weekly(profile: Profile) -> Self {
+        Self {
+            name: format!("Weekly Status Update Digest ({})", profile.name),
+            profile,
+            period: Period::Weekly,
+        }
+    }
+
+    pub fn monthly(profile: Profile) -> Self {
+        Self {
+            name: format!("Monthly Status Update Digest ({})", profile.name),
+            profile,
+            period: Period::Monthly,
+        }
+    }
+}
+
+#[async_trait]
+impl Task for Digest {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run_in(&self) -> tokio::time::Duration {
+        let schedule = &self.profile.schedule;
+        match self.period {
+            Period::Weekly => schedule.time_until_weekday(Weekday::Sun, DIGEST_TIME),
+            Period::Monthly => schedule.time_until_day_of_month(1, DIGEST_TIME),
+        }
+    }
+
+    async fn run(&self, ctx: Context) -> anyhow::Result<()> {
+        send_digest(ctx, &self.profile, self.period).await
+    }
+}
+
+/// Returns the first and last dates covered by a digest sent on `today`, along with those of the
+/// period before it.
+fn date_ranges(
+    period: Period,
+    today: NaiveDate,
+) -> ((NaiveDate, NaiveDate), (NaiveDate, NaiveDate)) {
+    match period {
+        Period::Weekly => {
+            let start = today - Duration::days(6);
+            (
+                (start, today),
+                (start - Duration::days(7), start - Duration::days(1)),
+            )
+        }
+        Period::Monthly => {
+            let end = today.with_day(1).expect("Every month has a first day") - Duration::days(1);
+            let start = end.with_day(1).expect("Every month has a first day");
+            let previous_end = start - Duration::days(1);
+            let previous_start = previous_end
+                .with_day(1)
+                .expect("Every month has a first day");
+            ((start, end), (previous_start, previous_end))
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    name: String,
+    group_id: Option<i32>,
+    accepted: u32,
+    missed: u32,
+    latest: Option<(NaiveDate, i32)>,
+}
+
+impl Stats {
+    fn add(&mut self, outcome: &DailyOutcome) {
+        self.name.clone_from(&outcome.name);
+        self.group_id = outcome.group_id;
+        match outcome.outcome {
+            Outcome::Accepted | Outcome::Late | Outcome::MadeUp => self.accepted += 1,
+            Outcome::Missed | Outcome::Frozen => self.missed += 1,
+            Outcome::Excused => {}
+        }
+        if self.latest.is_none_or(|(date, _)| date < outcome.date) {
+            self.latest = Some((outcome.date, outcome.streak_after));
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        let total = self.accepted + self.missed;
+        if total == 0 {
+            0.0
+        } else {
+            f64::from(self.accepted) / f64::from(total)
+        }
+    }
+
+    fn streak(&self) -> i32 {
+        self.latest.map_or(0, |(_, streak)| streak)
+    }
+}
+
+fn per_member(outcomes: &[DailyOutcome], start: NaiveDate, end: NaiveDate) -> HashMap<i32, Stats> {
+    let mut stats: HashMap<i32, Stats> = HashMap::new();
+    for outcome in outcomes.iter().filter(|o| o.date >= start && o.date <= end) {
+        stats.entry(outcome.member_id).or_default().add(outcome);
+    }
+
+    stats
+}
+
+async fn send_digest(ctx: Context, profile: &Profile, period: Period) -> anyhow::Result<()> {
+    trace!("Starting send_digest for {:?} of {}", period, profile.name);
+    let today = profile.schedule.today();
+    let ((start, end), (previous_start, previous_end)) = date_ranges(period, today);
+    let outcomes = Query::default()
+        .profile(&profile.name)
+        .since(previous_start)
+        .until(end)
+        .fetch()
+        .context("Failed to read outcome history")?;
+    debug!("Outcomes for digest: {:?}", outcomes);
+
+    let current = per_member(&outcomes, start, end);
+    let previous = per_member(&outcomes, previous_start, previous_end);
+    let description = build_description(&current, &previous);
+
+    let title = match period {
+        Period::Weekly => format!("Weekly Status Update Digest - {} to {}", start, end),
+        Period::Monthly => format!("Monthly Status Update Digest - {}", start.format("%B %Y")),
+    };
+    // Every member is listed, so large cohorts need several embeds.
+    let file_name = format!("digest-{}-{}.md", profile.name, end);
+    let messages = layout::layout(&title, &description, &file_name, |embed| {
+        embed
+            .color(serenity::all::Colour::new(0xeab308))
+            .timestamp(Timestamp::now())
+            .author(discord::author())
+    });
+    for message in messages {
+        profile
+            .report_channel
+            .send_message(&ctx.http, message)
+            .await
+            .context("Failed to send digest")?;
+    }
+
+    Ok(())
+}
+
+fn build_description(current: &HashMap<i32, Stats>, previous: &HashMap<i32, Stats>) -> String {
+    if current.is_empty() {
+        return String::from("No status updates were evaluated in this period.");
+    }
+
+    let mut desc = String::from("# Submission Rates\n## By Group\n");
+    let mut groups: BTreeMap<Option<i32>, (u32, u32)> = BTreeMap::new();
+    for stats in current.values() {
+        let group = groups.entry(stats.group_id).or_default();
+        group.0 += stats.accepted;
+        group.1 += stats.accepted + stats.missed;
+    }
+    for (group_id, (accepted, total)) in groups {
+        let name = group_id.map_or_else(|| "No group".to_string(), |id| format!("Group {}", id));
+        desc.push_str(&format!(
+            "- {} - {:.0}% ({}/{})\n",
+            name,
+            f64::from(accepted) / f64::from(total.max(1)) * 100.0,
+            accepted,
+            total
+        ));
+    }
+
+    let mut members: Vec<&Stats> = current.values().collect();
+    members.sort_by(|a, b| b.rate().total_cmp(&a.rate()).then(a.name.cmp(&b.name)));
+    desc.push_str("## By Member\n");
+    for stats in &members {
+        desc.push_str(&format!(
+            "- {} - {:.0}% ({}/{})\n",
+            stats.name,
+            stats.rate() * 100.0,
+            stats.accepted,
+            stats.accepted + stats.missed
+        ));
+    }
+
+    let mut streaks: Vec<&Stats> = members.iter().copied().filter(|s| s.streak() > 0).collect();
+    streaks.sort_by_key(|s| std::cmp::Reverse(s.streak()));
+    if !streaks.is_empty() {
+        desc.push_str("# Longest Active Streaks\n");
+        for stats in streaks.iter().take(MAX_LISTED) {
+            desc.push_str(&format!("- {} - {}\n", stats.name, stats.streak()));
+        }
+    }
+
+    let mut improved: Vec<(&Stats, f64)> = current
+        .iter()
+        .filter_map(|(id, stats)| {
+            let before = previous.get(id)?;
+            let delta = stats.rate() - before.rate();
+            (delta > 0.0).then_some((stats, delta))
+        })
+        .collect();
+    improved.sort_by(|a, b| b.1.total_cmp(&a.1));
+    if !improved.is_empty() {
+        desc.push_str("# Most Improved\n");
+        for (stats, delta) in improved.iter().take(MAX_LISTED) {
+            desc.push_str(&format!("- {} - +{:.0}%\n", stats.name, delta * 100.0));
+        }
+    }
+
+    let chronic: Vec<&Stats> = members
+        .iter()
+        .rev()
+        .copied()
+        .filter(|s| s.rate() < CHRONIC_MISS_RATE)
+        .collect();
+    if !chronic.is_empty() {
+        desc.push_str("# Chronic Misses\n");
+        for stats in chronic {
+            desc.push_str(&format!(
+                "- {} - missed {} of {}\n",
+                stats.name,
+                stats.missed,
+                stats.accepted + stats.missed
+            ));
+        }
+    }
+
+    debug!("Digest description: {}", desc);
+    desc
+}
@@ -15,14 +15,20 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
+mod digest;
+mod reminder;
 mod status_update;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use digest::Digest;
+use reminder::StatusUpdateReminder;
 use serenity::client::Context;
 use status_update::StatusUpdateCheck;
 use tokio::time::Duration;
 
+use crate::updates::profile::Profile;
+
 /// A [`Task`] is any job that needs to be executed on a regular basis.
 /// A task has a function [`Task::run_in`] that returns the time till the
 /// next ['Task::run`] is run. It also has a [`Task::name`] that can be used
@@ -36,6 +42,15 @@ pub trait Task: Send + Sync {
 
 /// Analogous to [`crate::commands::get_commands`], every task that is defined
 /// must be included in the returned vector in order for it to be scheduled.
-pub fn get_tasks() -> Vec<Box<dyn Task>> {
-    vec![Box::new(StatusUpdateCheck)]
+/// Status update tasks are created once for every [`Profile`].
+pub fn get_tasks(profiles: &[Profile]) -> Vec<Box<dyn Task>> {
+    let mut tasks: Vec<Box<dyn Task>> = vec![];
+    for profile in profiles {
+        tasks.push(Box::new(StatusUpdateCheck::new(profile.clone())));
+        tasks.push(Box::new(StatusUpdateReminder::new(profile.clone())));
+        tasks.push(Box::new(Digest::weekly(profile.clone())));
+        tasks.push(Box::new(Digest::monthly(profile.clone())));
+    }
+
+    tasks
 }
@@ -0,0 +1,134 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use serenity::all::{ChannelId, Context, CreateMessage, UserId};
+use serenity::async_trait;
+use tracing::{debug, error, trace, warn};
+
+use std::collections::HashSet;
+
+use super::Task;
+use crate::{
+    calendar,
+    graphql::{models::Member, queries::fetch_members},
+    storage::{preferences, updates},
+    updates::profile::Profile,
+};
+
+/// Reminds members who haven't sent their status update yet before the collection window closes.
+pub struct StatusUpdateReminder {
+    name: String,
+    profile: Profile,
+}
+
+impl StatusUpdateReminder {
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            name: format!("Status Update Reminder ({})", profile.name),
+            profile,
+        }
+    }
+}
+
+#[async_trait]
+impl Task for StatusUpdateReminder {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run_in(&self) -> tokio::time::Duration {
+        let schedule = &self.profile.schedule;
+        schedule.time_until_next(schedule.reminder_time)
+    }
+
+    async fn run(&self, ctx: Context) -> anyhow::Result<()> {
+        send_reminders(ctx, &self.profile).await
+    }
+}
+
+async fn send_reminders(ctx: Context, profile: &Profile) -> anyhow::Result<()> {
+    trace!("Starting send_reminders");
+    let members = fetch_members()
+        .await
+        .context("Failed to fetch members from Root.")?;
+
+    let window = profile.schedule.upcoming_window();
+    if let Some((mode, reason)) = calendar::day_mode(profile, window.start.date_naive())? {
+        debug!("Not sending reminders, the day is {:?} ({})", mode, reason);
+        return Ok(());
+    }
+    let authors: HashSet<UserId> = updates::between(window.start.into(), window.end.into())
+        .context("Failed to read recorded updates")?
+        .into_iter()
+        .filter(|u| profile.channels.contains(&u.channel_id))
+        .map(|u| u.author_id)
+        .collect();
+    debug!("Members who already sent their update: {:?}", authors);
+
+    for member in members.iter().filter(|m| profile.includes(m)) {
+        let Ok(user_id) = member.discord_id.parse::<u64>().map(UserId::new) else {
+            warn!("Member {} has an invalid Discord ID", member.name);
+            continue;
+        };
+        if authors.contains(&user_id) || !preferences::get(user_id)?.reminders {
+            continue;
+        }
+
+        if let Err(e) = remind(&ctx, member, user_id).await {
+            error!("Could not remind {}. Error: {}", member.name, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// DMs `member` a reminder. If their DMs are closed, pings them in the channel they last sent an
+/// update in instead.
+async fn remind(ctx: &Context, member: &Member, user_id: UserId) -> anyhow::Result<()> {
+    let streak = member.streak.first().map_or(0, |s| s.current_streak);
+    let at_stake = if streak > 0 {
+        format!(" Your streak of {} is at stake!", streak)
+    } else {
+        String::new()
+    };
+    let content = format!(
+        "You haven't sent your status update yet.{} Use `/reminders off` to stop these reminders.",
+        at_stake
+    );
+
+    debug!("Reminding {}", member.name);
+    let dm = async {
+        user_id
+            .create_dm_channel(&ctx.http)
+            .await?
+            .send_message(&ctx.http, CreateMessage::new().content(&content))
+            .await
+    };
+    if dm.await.is_ok() {
+        return Ok(());
+    }
+
+    let channel_id: ChannelId = updates::latest_channel(user_id)?
+        .context("DMs are closed and no group channel is known")?;
+    channel_id
+        .say(&ctx.http, format!("<@{}> {}", user_id, content))
+        .await
+        .context("Failed to ping member in their group channel")?;
+
+    Ok(())
+}
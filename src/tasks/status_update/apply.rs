@@ -0,0 +1,165 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use tracing::{debug, trace};
+
+use std::collections::HashMap;
+
+use crate::{
+    graphql::{
+        models::Member,
+        queries::{fetch_members, increment_streak, reset_streak, restore_streak},
+    },
+    storage::{
+        freezes,
+        history::{self, DailyOutcome, Outcome},
+        runs::{self, PlannedMember, Run, Stage},
+    },
+    updates::profile::Profile,
+};
+
+/// Applies the changes planned in `run` to Root and to the local records, saving the progress
+/// after every step. Members that were already applied are skipped, so a failed run can be
+/// resumed. A member whose Root update succeeded but could not be saved may still be applied
+/// twice.
+pub async fn apply(profile: &Profile, run: &mut Run) -> anyhow::Result<()> {
+    if run.members.iter().all(|m| m.stage == Stage::Done) {
+        return Ok(());
+    }
+
+    trace!("Applying the report of {} for {}", run.profile, run.date);
+    // Make-ups restore a streak up to a target, so they rely on the current streaks to not be
+    // applied twice.
+    let current: HashMap<i32, Member> = fetch_members()
+        .await
+        .context("Failed to fetch members from Root.")?
+        .into_iter()
+        .map(|m| (m.member_id, m))
+        .collect();
+
+    for i in 0..run.members.len() {
+        let planned = &mut run.members[i];
+        if planned.stage == Stage::Pending {
+            let mut member = current
+                .get(&planned.member.member_id)
+                .cloned()
+                .unwrap_or_else(|| planned.member.clone());
+            update_streak(planned, &mut member).await.with_context(|| {
+                format!("Failed to update the streak of {}", planned.member.name)
+            })?;
+            planned.streak = member.streak.first().cloned();
+            planned.stage = Stage::StreakUpdated;
+            runs::save(run).context("Failed to save run")?;
+        }
+
+        // Freeze tokens aren't keyed by date, so they are saved as updated before the history
+        // is written to never be counted twice.
+        let planned = &mut run.members[i];
+        if planned.stage == Stage::StreakUpdated {
+            update_freezes(profile, planned).with_context(|| {
+                format!(
+                    "Failed to update the freeze tokens of {}",
+                    planned.member.name
+                )
+            })?;
+            planned.stage = Stage::FreezesUpdated;
+            runs::save(run).context("Failed to save run")?;
+        }
+
+        let planned = &mut run.members[i];
+        if planned.stage == Stage::FreezesUpdated {
+            record(profile, run.date, planned).with_context(|| {
+                format!("Failed to record the outcome of {}", planned.member.name)
+            })?;
+            planned.stage = Stage::Done;
+            runs::save(run).context("Failed to save run")?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn update_streak(planned: &PlannedMember, member: &mut Member) -> anyhow::Result<()> {
+    if let Some(restored) = &planned.makeup {
+        debug!(
+            "Restoring the streak of {} to {}",
+            member.name, restored.streak_after
+        );
+        restore_streak(member, restored.streak_after)
+            .await
+            .context("Failed to restore streak")?;
+    }
+
+    match planned.outcome {
+        Outcome::Accepted | Outcome::Late => increment_streak(member)
+            .await
+            .context("Failed to increment streak"),
+        Outcome::Missed => reset_streak(member).await.context("Failed to reset streak"),
+        Outcome::Excused | Outcome::Frozen | Outcome::MadeUp => Ok(()),
+    }
+}
+
+/// Updates the freeze tokens of the member of `planned`.
+fn update_freezes(profile: &Profile, planned: &PlannedMember) -> anyhow::Result<()> {
+    let member = &planned.member;
+    if let Some(policy) = &profile.freezes {
+        match planned.outcome {
+            Outcome::Accepted | Outcome::Late => {
+                if freezes::on_accepted(&member.discord_id, policy)
+                    .context("Failed to update freeze tokens")?
+                {
+                    debug!("{} earned a freeze token", member.name);
+                }
+            }
+            Outcome::Missed | Outcome::Frozen => {
+                freezes::on_missed(&member.discord_id).context("Failed to spend freeze token")?;
+            }
+            Outcome::Excused | Outcome::MadeUp => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Records the outcome of the member of `planned` in the history.
+fn record(
+    profile: &Profile,
+    date: chrono::NaiveDate,
+    planned: &PlannedMember,
+) -> anyhow::Result<()> {
+    let member = &planned.member;
+    if let Some(restored) = &planned.makeup {
+        history::record(restored.clone()).context("Failed to record make-up")?;
+    }
+    history::record(DailyOutcome {
+        profile: profile.name.clone(),
+        date,
+        member_id: member.member_id,
+        name: member.name.clone(),
+        discord_id: member.discord_id.clone(),
+        group_id: member.group_id,
+        outcome: planned.outcome,
+        message_link: planned.message_link.clone(),
+        streak_before: planned.streak_before,
+        streak_after: planned
+            .streak
+            .as_ref()
+            .map_or(planned.streak_before, |s| s.current_streak),
+    })
+    .context("Failed to record outcome")
+}
@@ -0,0 +1,143 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+/// Applies the planned outcomes to Root and to the local records.
+mod apply;
+/// Works out every member's outcome without changing anything.
+mod plan;
+/// Posts the report of an applied run.
+mod publish;
+
+use anyhow::Context as _;
+use serenity::all::Context;
+use serenity::async_trait;
+use tracing::{debug, error, trace, warn};
+
+use super::Task;
+use crate::{
+    storage::runs::{self, Run},
+    updates::profile::Profile,
+};
+
+/// How long to wait before resuming a run that failed.
+const RETRY_DELAY: tokio::time::Duration = tokio::time::Duration::from_secs(5 * 60);
+
+/// Checks for status updates daily at the report time of its [`Profile`].
+pub struct StatusUpdateCheck {
+    name: String,
+    profile: Profile,
+}
+
+impl StatusUpdateCheck {
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            name: format!("Status Update Check ({})", profile.name),
+            profile,
+        }
+    }
+}
+
+#[async_trait]
+impl Task for StatusUpdateCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run_in(&self) -> tokio::time::Duration {
+        match runs::unfinished(&self.profile.name) {
+            Ok(unfinished) if !unfinished.is_empty() => RETRY_DELAY,
+            _ => self.profile.schedule.time_until_report(),
+        }
+    }
+
+    async fn run(&self, ctx: Context) -> anyhow::Result<()> {
+        check_status_updates(ctx, &self.profile).await
+    }
+}
+
+/// Resumes the runs of `profile` that failed before, then plans, applies and publishes the report
+/// that fell due today if it hasn't run yet. Failed attempts are logged and counted instead of
+/// returned, so that a failed resume doesn't hold up today's report and a failed run is retried
+/// after [`RETRY_DELAY`].
+pub async fn check_status_updates(ctx: Context, profile: &Profile) -> anyhow::Result<()> {
+    trace!("Starting check_status_updates for {}", profile.name);
+    for run in runs::unfinished(&profile.name).context("Failed to read unfinished runs")? {
+        debug!("Resuming the run of {}", run.date);
+        attempt(&ctx, profile, run).await?;
+    }
+
+    let schedule = &profile.schedule;
+    let date = schedule.last_due_report();
+    // Only the report that fell due today is run, so that a restart never reruns an old one.
+    if (schedule.report_at(date) + schedule.grace).date_naive() != schedule.today() {
+        debug!("No report is due yet today");
+        return Ok(());
+    }
+    if runs::get(&profile.name, date)?.is_some() {
+        debug!("The report of {} already ran", date);
+        return Ok(());
+    }
+
+    // Saved before planning, so that the report is retried even if planning fails.
+    let run = Run::unplanned(profile.name.clone(), date);
+    runs::save(&run).context("Failed to save run")?;
+    attempt(&ctx, profile, run).await
+}
+
+/// Completes `run`, counting a failure towards [`runs::MAX_ATTEMPTS`].
+async fn attempt(ctx: &Context, profile: &Profile, run: Run) -> anyhow::Result<()> {
+    let date = run.date;
+    if let Err(e) = complete(ctx, profile, run).await {
+        error!("Could not run the report of {}. Error: {:?}", date, e);
+        count_failed_attempt(profile, date)?;
+    }
+
+    Ok(())
+}
+
+/// Counts a failed attempt at the run of `profile` on `date`.
+fn count_failed_attempt(profile: &Profile, date: chrono::NaiveDate) -> anyhow::Result<()> {
+    let Some(mut run) = runs::get(&profile.name, date)? else {
+        return Ok(());
+    };
+    run.attempts += 1;
+    if run.attempts >= runs::MAX_ATTEMPTS {
+        warn!(
+            "Giving up on the report of {} after {} failed attempts",
+            date, run.attempts
+        );
+    }
+
+    runs::save(&run).context("Failed to save run")
+}
+
+async fn complete(ctx: &Context, profile: &Profile, mut run: Run) -> anyhow::Result<()> {
+    if !run.planned {
+        let attempts = run.attempts;
+        run = plan::plan(profile, run.date)
+            .await
+            .with_context(|| format!("Failed to plan the report of {}", run.date))?;
+        run.attempts = attempts;
+        runs::save(&run).context("Failed to save run")?;
+    }
+    apply::apply(profile, &mut run)
+        .await
+        .with_context(|| format!("Failed to apply the report of {}", run.date))?;
+    publish::publish(ctx, profile, &mut run)
+        .await
+        .with_context(|| format!("Failed to publish the report of {}", run.date))
+}
@@ -0,0 +1,270 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use chrono::NaiveDate;
+use serenity::all::{MessageId, Timestamp};
+use tracing::{debug, trace};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    calendar,
+    graphql::{models::Member, queries::fetch_members},
+    storage::{
+        calendar::DayMode,
+        freezes,
+        history::{DailyOutcome, Outcome, Query},
+        overrides::{self, Override, Verdict},
+        runs::{PlannedMember, Run, Stage},
+        updates::{self, RecordedUpdate},
+    },
+    updates::{
+        profile::Profile,
+        similarity::{self, SimilarUpdate},
+    },
+    utils::time::CollectionWindow,
+};
+
+/// How far back an update is compared with the author's earlier updates.
+const SIMILARITY_LOOKBACK_DAYS: i64 = 7;
+
+/// Works out the outcome of every member of `profile` for the report of `date`, without changing
+/// anything in Root or in the local records.
+pub async fn plan(profile: &Profile, date: NaiveDate) -> anyhow::Result<Run> {
+    trace!("Planning the report of {} for {}", profile.name, date);
+    let members = fetch_members()
+        .await
+        .context("Failed to fetch members from Root.")?;
+    debug!("Members fetched from root: {:?}", members);
+    let window = profile
+        .schedule
+        .window_for(profile.schedule.report_at(date));
+    debug!("Collection window: {:?}", window);
+    let mut run = Run::new(profile.name.clone(), date, window.describe());
+
+    // Updates are due on the day the window opens, so that is the day the calendar is checked for.
+    let day_mode = calendar::day_mode(profile, window.start.date_naive())
+        .context("Failed to check the calendar")?;
+    debug!("Day mode: {:?}", day_mode);
+    match day_mode {
+        Some((DayMode::Skip, reason)) => {
+            run.skip_reason = Some(reason);
+            return Ok(run);
+        }
+        Some((DayMode::Optional, reason)) => run.optional_reason = Some(reason),
+        None => {}
+    }
+
+    let overrides = collect_overrides(profile, &window).context("Failed to collect overrides")?;
+    debug!("Overrides: {:?}", overrides);
+    let collected =
+        collect_updates(profile, &window, &overrides).context("Failed to collect updates")?;
+    let similar = find_similar_updates(profile, &window, &collected)
+        .context("Failed to check updates for similarity")?;
+    debug!("Similar updates: {:?}", similar);
+    // Mentors have the final say, so updates they accepted are never rejected as similar.
+    let rejected: HashSet<MessageId> = if profile.rules.reject_similar {
+        similar
+            .iter()
+            .map(|s| s.update.message_id)
+            .filter(|id| {
+                !overrides
+                    .iter()
+                    .any(|o| o.verdict == Verdict::Accept && o.update.message_id == *id)
+            })
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let (makeups, updates): (Vec<&RecordedUpdate>, Vec<&RecordedUpdate>) = collected
+        .iter()
+        .filter(|u| !rejected.contains(&u.message_id))
+        .partition(|u| profile.rules.is_makeup(&u.content));
+    debug!("Updates collected: {:?}", updates);
+    debug!("Make-up updates collected: {:?}", makeups);
+
+    let message_authors = earliest_by_author(updates);
+    debug!("Message authors: {:?}", message_authors.keys());
+    let makeup_authors = earliest_by_author(makeups);
+
+    let window_end: Timestamp = window.end.into();
+    for member in members.into_iter().filter(|m| profile.includes(m)) {
+        debug!("Planning member: {:?}", member);
+        let makeup = match makeup_authors.get(&member.discord_id) {
+            Some(makeup) => plan_makeup(profile, date, &member, makeup)
+                .context("Failed to check make-up update")?,
+            None => None,
+        };
+        let update = message_authors.get(&member.discord_id);
+        let streak_before = makeup
+            .as_ref()
+            .map_or(member.streak.first().map_or(0, |s| s.current_streak), |m| {
+                m.streak_after
+            });
+
+        let mut tokens_left = None;
+        let outcome = match update {
+            Some(u) if u.timestamp >= window_end => Outcome::Late,
+            Some(_) => Outcome::Accepted,
+            None if run.optional_reason.is_some() => Outcome::Excused,
+            None => {
+                let tokens = match profile.freezes {
+                    Some(_) => freezes::get(&member.discord_id)?.tokens,
+                    None => 0,
+                };
+                if tokens > 0 {
+                    tokens_left = Some(tokens - 1);
+                    Outcome::Frozen
+                } else {
+                    Outcome::Missed
+                }
+            }
+        };
+
+        run.members.push(PlannedMember {
+            member,
+            outcome,
+            message_link: update.map(|u| u.link()),
+            streak_before,
+            makeup,
+            tokens_left,
+            stage: Stage::Pending,
+            streak: None,
+        });
+    }
+
+    run.updates = collected;
+    run.similar = similar;
+    run.overrides = overrides;
+    Ok(run)
+}
+
+/// Returns the earliest of `updates` by each author, so that a second message in the grace period
+/// doesn't make an update that was on time late.
+fn earliest_by_author(updates: Vec<&RecordedUpdate>) -> HashMap<String, &RecordedUpdate> {
+    let mut earliest: HashMap<String, &RecordedUpdate> = HashMap::new();
+    for update in updates {
+        earliest
+            .entry(update.author_id.to_string())
+            .and_modify(|e| {
+                if update.timestamp < e.timestamp {
+                    *e = update;
+                }
+            })
+            .or_insert(update);
+    }
+
+    earliest
+}
+
+/// Returns the updates recorded by the event handler in the channels of `profile` during `window`,
+/// including its grace period. Messages mentors accepted are added and those they rejected are
+/// left out.
+fn collect_updates(
+    profile: &Profile,
+    window: &CollectionWindow,
+    overrides: &[Override],
+) -> anyhow::Result<Vec<RecordedUpdate>> {
+    trace!("Collecting updates");
+    let mut valid_updates = updates::between(window.start.into(), window.grace_end.into())
+        .context("Failed to read recorded updates")?;
+    valid_updates.retain(|u| profile.channels.contains(&u.channel_id));
+    valid_updates.retain(|u| {
+        !overrides
+            .iter()
+            .any(|o| o.verdict == Verdict::Reject && o.update.message_id == u.message_id)
+    });
+    for accepted in overrides.iter().filter(|o| o.verdict == Verdict::Accept) {
+        if !valid_updates
+            .iter()
+            .any(|u| u.message_id == accepted.update.message_id)
+        {
+            valid_updates.push(accepted.update.clone());
+        }
+    }
+
+    debug!("Valid updates: {:?}", valid_updates);
+    Ok(valid_updates)
+}
+
+/// Returns the mentors' decisions on messages posted in the channels of `profile` during `window`,
+/// including its grace period.
+fn collect_overrides(
+    profile: &Profile,
+    window: &CollectionWindow,
+) -> anyhow::Result<Vec<Override>> {
+    let mut overrides = overrides::between(window.start.into(), window.grace_end.into())
+        .context("Failed to read overrides")?;
+    overrides.retain(|o| profile.channels.contains(&o.update.channel_id));
+
+    Ok(overrides)
+}
+
+/// Checks `collected` against the updates of the last [`SIMILARITY_LOOKBACK_DAYS`] days, if
+/// `profile` has the check enabled.
+fn find_similar_updates(
+    profile: &Profile,
+    window: &CollectionWindow,
+    collected: &[RecordedUpdate],
+) -> anyhow::Result<Vec<SimilarUpdate>> {
+    let Some(threshold) = profile.rules.similarity_threshold else {
+        return Ok(vec![]);
+    };
+
+    let lookback_start = window.start - chrono::Duration::days(SIMILARITY_LOOKBACK_DAYS);
+    let mut recent = updates::between(lookback_start.into(), window.start.into())
+        .context("Failed to read recent updates")?;
+    recent.retain(|u| profile.channels.contains(&u.channel_id));
+
+    Ok(similarity::find_similar(
+        collected,
+        &recent,
+        &profile.rules,
+        threshold,
+    ))
+}
+
+/// Returns the last day `member` missed, as it will be recorded once `makeup` restores it, if it
+/// can still be made up. Make-ups are only accepted within 24 hours of the report of the missed
+/// day.
+fn plan_makeup(
+    profile: &Profile,
+    date: NaiveDate,
+    member: &Member,
+    makeup: &RecordedUpdate,
+) -> anyhow::Result<Option<DailyOutcome>> {
+    let Some(mut missed) = Query::default()
+        .profile(&profile.name)
+        .discord_id(&member.discord_id)
+        .until(date - chrono::Duration::days(1))
+        .fetch()?
+        .pop()
+    else {
+        return Ok(None);
+    };
+    let deadline: Timestamp =
+        (profile.schedule.report_at(missed.date) + chrono::Duration::hours(24)).into();
+    if missed.outcome != Outcome::Missed || makeup.timestamp > deadline {
+        return Ok(None);
+    }
+
+    missed.outcome = Outcome::MadeUp;
+    missed.message_link = Some(makeup.link());
+    missed.streak_after = missed.streak_before.max(0) + 1;
+    Ok(Some(missed))
+}
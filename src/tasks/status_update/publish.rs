@@ -0,0 +1,214 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use serenity::all::{ChannelId, Context, CreateMessage, CreateThread, Message, Timestamp};
+use tracing::{debug, trace};
+
+use crate::{
+    report::{
+        discord::{self, DiscordRenderer},
+        layout, markdown, DailyReport, ReportRenderer,
+    },
+    storage::{
+        history::Outcome,
+        preferences,
+        runs::{self, PlannedMember, Run},
+        updates::RecordedUpdate,
+    },
+    updates::{escalation, profile::Profile},
+};
+
+/// Sends the mentor digest, the report of an applied `run`, the thread of update links on it and
+/// each group's numbers, then runs the escalations. Progress is saved after every message, so
+/// that a resumed run doesn't post anything twice.
+pub async fn publish(ctx: &Context, profile: &Profile, run: &mut Run) -> anyhow::Result<()> {
+    trace!("Publishing the report of {} for {}", run.profile, run.date);
+    if !run.digest_sent {
+        if let Some(channel) = profile.mentors_channel {
+            let messages = mentor_digest(profile, run);
+            send_all(ctx, run, channel, messages, |_, _| {})
+                .await
+                .context("Failed to send mentor digest")?;
+        }
+        run.digest_sent = true;
+        runs::save(run).context("Failed to save run")?;
+    }
+
+    let renderer = DiscordRenderer {
+        mentions: &profile.mentions,
+        opted_in: preferences::opted_in_to_pings().context("Failed to read preferences")?,
+        links: profile.links,
+        groups: profile.breakdown.in_report,
+    };
+    let report = DailyReport::from_run(run);
+    if !run.report_sent {
+        debug!("Sending report...");
+        let messages = renderer.render(&report);
+        send_all(ctx, run, profile.report_channel, messages, |run, sent| {
+            run.report_message.get_or_insert(sent.id);
+        })
+        .await
+        .context("Failed to send status update report")?;
+        run.report_sent = true;
+        runs::save(run).context("Failed to save run")?;
+    }
+
+    if !run.links_sent {
+        let messages = renderer.thread(&report);
+        if let (Some(message_id), false) = (run.report_message, messages.is_empty()) {
+            debug!("Sending update links...");
+            let thread = match run.links_thread {
+                Some(thread) => thread,
+                None => {
+                    let thread = profile
+                        .report_channel
+                        .create_thread_from_message(
+                            &ctx.http,
+                            message_id,
+                            CreateThread::new(format!("Accepted updates - {}", run.date)),
+                        )
+                        .await
+                        .context("Failed to create thread for update links")?;
+                    run.links_thread = Some(thread.id);
+                    runs::save(run).context("Failed to save run")?;
+                    thread.id
+                }
+            };
+            send_all(ctx, run, thread, messages, |_, _| {})
+                .await
+                .context("Failed to send update links")?;
+        }
+        run.links_sent = true;
+        runs::save(run).context("Failed to save run")?;
+    }
+
+    if !run.breakdown_sent {
+        for group in &report.groups {
+            let channel = group
+                .group
+                .and_then(|id| profile.breakdown.channels.get(&id));
+            let Some(channel) = channel else {
+                continue;
+            };
+            if run.groups_sent.contains(&group.group) {
+                continue;
+            }
+
+            debug!("Sending breakdown of {}...", group.label());
+            let messages = renderer.group(&report, group);
+            send_all(ctx, run, *channel, messages, |_, _| {})
+                .await
+                .context("Failed to send group breakdown")?;
+            run.groups_sent.push(group.group);
+            runs::save(run).context("Failed to save run")?;
+        }
+        run.breakdown_sent = true;
+        runs::save(run).context("Failed to save run")?;
+    }
+
+    if !run.escalated {
+        escalation::escalate(ctx, profile, run.date)
+            .await
+            .context("Failed to run escalations")?;
+        run.escalated = true;
+        runs::save(run).context("Failed to save run")?;
+    }
+
+    Ok(())
+}
+
+/// Sends the messages of the current step of `run` to `channel`, skipping those a failed attempt
+/// already sent. `on_sent` is called with every message that is sent, before the run is saved.
+async fn send_all(
+    ctx: &Context,
+    run: &mut Run,
+    channel: ChannelId,
+    messages: Vec<CreateMessage>,
+    mut on_sent: impl FnMut(&mut Run, &Message),
+) -> anyhow::Result<()> {
+    for message in messages.into_iter().skip(run.messages_sent) {
+        let sent = channel.send_message(&ctx.http, message).await?;
+        on_sent(run, &sent);
+        run.messages_sent += 1;
+        runs::save(run).context("Failed to save run")?;
+    }
+    // Saved along with the end of the step by the caller.
+    run.messages_sent = 0;
+
+    Ok(())
+}
+
+/// Returns the messages that list every blocker reported in the counted updates of `run` for the
+/// mentors, so that they don't get buried in the group channels, along with the updates that were
+/// flagged as similar. Empty if there is nothing to list.
+fn mentor_digest(profile: &Profile, run: &Run) -> Vec<CreateMessage> {
+    trace!("Running mentor_digest for {}", profile.name);
+    // Only the updates that counted, so that rejected ones and those of excluded members are left
+    // out.
+    let blocked: Vec<(&PlannedMember, &RecordedUpdate)> = run
+        .members
+        .iter()
+        .filter(|m| matches!(m.outcome, Outcome::Accepted | Outcome::Late))
+        .filter_map(|m| {
+            let link = m.message_link.as_ref()?;
+            let update = run.updates.iter().find(|u| &u.link() == link)?;
+            Some((m, update))
+        })
+        .filter(|(_, update)| !update.parsed.blockers.is_empty())
+        .collect();
+
+    let mut description = String::new();
+    if !blocked.is_empty() {
+        description.push_str("# Blockers\n");
+    }
+    for (planned, update) in blocked {
+        let group = planned
+            .member
+            .group_id
+            .map_or("No group".to_string(), |g| format!("Group {}", g));
+        description.push_str(&format!(
+            "### {} ({}) - [update]({})\n",
+            markdown::escape(&update.author_name),
+            group,
+            update.link()
+        ));
+        for blocker in &update.parsed.blockers {
+            description.push_str(&format!("- {}\n", blocker));
+        }
+    }
+
+    description.push_str(&markdown::flagged(&DailyReport::from_run(run)));
+
+    if description.is_empty() {
+        debug!("Nothing to report to mentors");
+        return vec![];
+    }
+
+    let file_name = format!("mentor-digest-{}-{}.md", run.profile, run.date);
+    layout::layout(
+        &format!("Mentor Digest - {}", run.date),
+        &description,
+        &file_name,
+        |embed| {
+            embed
+                .color(serenity::all::Colour::new(0xef4444))
+                .timestamp(Timestamp::now())
+                .author(discord::author())
+        },
+    )
+}
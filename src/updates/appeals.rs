@@ -0,0 +1,342 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use serenity::all::{
+    ButtonStyle, Colour, ComponentInteraction, Context, CreateActionRow, CreateAllowedMentions,
+    CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage, User, UserId,
+};
+use tracing::{debug, trace, warn};
+
+use super::is_mentor;
+use crate::{
+    graphql::queries::{fetch_members, restore_streak},
+    report::discord,
+    storage::{
+        appeals::{self, Appeal, AppealStatus},
+        history::{self, Outcome, Query},
+        runs,
+    },
+    Data,
+};
+
+/// Prefix of the custom IDs of the buttons on an appeal.
+const BUTTON_PREFIX: &str = "appeal:";
+
+/// Records `author`'s appeal of the report that the message at `link` should have counted for, and
+/// asks the mentors to decide on it. Returns the reply for the member.
+pub async fn file(ctx: &Context, data: &Data, author: &User, link: &str) -> anyhow::Result<String> {
+    let Some((guild_id, channel_id, message_id)) = serenity::utils::parse_message_url(link) else {
+        return Ok("That isn't a link to a message.".to_string());
+    };
+    let msg = channel_id
+        .message(&ctx.http, message_id)
+        .await
+        .context("Failed to fetch appealed message")?;
+    if msg.author.id != author.id {
+        return Ok("You can only appeal with an update you sent yourself.".to_string());
+    }
+
+    let discord_id = author.id.to_string();
+    for profile in &data.profiles {
+        let Some(date) = profile.schedule.report_date_of(*msg.timestamp) else {
+            continue;
+        };
+        let missed = Query::default()
+            .profile(&profile.name)
+            .discord_id(&discord_id)
+            .since(date)
+            .until(date)
+            .outcome(Outcome::Missed)
+            .fetch()
+            .context("Failed to read history")?;
+        if missed.is_empty() {
+            continue;
+        }
+
+        if appeals::find(&profile.name, date, &discord_id)?.is_some() {
+            return Ok(format!("You already appealed the report of {}.", date));
+        }
+        let Some(channel) = profile.mentors_channel else {
+            return Ok("There is no mentors channel to send your appeal to.".to_string());
+        };
+
+        let appeal = appeals::create(
+            &profile.name,
+            date,
+            &discord_id,
+            &author.name,
+            &message_id.link(channel_id, Some(guild_id)),
+        )
+        .context("Failed to record appeal")?;
+        debug!("{} appealed the report of {}", author.name, date);
+        channel
+            .send_message(
+                &ctx.http,
+                CreateMessage::new()
+                    .embed(embed(&appeal))
+                    .components(buttons(appeal.id)),
+            )
+            .await
+            .context("Failed to send appeal to mentors")?;
+
+        return Ok(format!(
+            "Your appeal of the report of {} was sent to the mentors.",
+            date
+        ));
+    }
+
+    Ok(
+        "You weren't marked as missing an update in the report that message would have counted \
+        for."
+            .to_string(),
+    )
+}
+
+/// Approves or denies an appeal when a mentor presses one of its buttons. Presses of any other
+/// button are ignored.
+pub async fn handle_component(
+    ctx: &Context,
+    data: &Data,
+    component: &ComponentInteraction,
+) -> anyhow::Result<()> {
+    let Some((action, id)) = component
+        .data
+        .custom_id
+        .strip_prefix(BUTTON_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+    else {
+        return Ok(());
+    };
+    let status = match action {
+        "approve" => AppealStatus::Approved,
+        "deny" => AppealStatus::Denied,
+        _ => return Ok(()),
+    };
+    let id: u64 = id.parse().context("Failed to parse appeal ID")?;
+    trace!(
+        "{} pressed {} on appeal {}",
+        component.user.name,
+        action,
+        id
+    );
+
+    let guild_id = component
+        .guild_id
+        .context("Appeal buttons are only sent in servers")?;
+    if !is_mentor(ctx, guild_id, component.user.id).await? {
+        return respond(ctx, component, "Only mentors can decide appeals.").await;
+    }
+    let appeal = appeals::get(id)?.context("Appeal does not exist")?;
+    // The appeal is claimed before anything is applied, so that two mentors can't both approve
+    // it. An approval that failed to apply can be retried with the same button.
+    let is_retry = appeal.status == AppealStatus::Approved
+        && status == AppealStatus::Approved
+        && !appeal.applied;
+    let mut appeal = if is_retry {
+        debug!("Retrying the approval of appeal {}", id);
+        appeal
+    } else {
+        match appeals::decide(id, status, component.user.id)? {
+            Some(appeal) => appeal,
+            None => return respond(ctx, component, "This appeal was already decided.").await,
+        }
+    };
+
+    let note = if status == AppealStatus::Approved {
+        let streak = approve(ctx, data, &mut appeal)
+            .await
+            .context("Failed to approve appeal")?;
+        format!(
+            "Approved by {}. The streak is back to {}.",
+            component.user.name, streak
+        )
+    } else {
+        format!("Denied by {}.", component.user.name)
+    };
+
+    let decided = component
+        .message
+        .embeds
+        .first()
+        .cloned()
+        .map_or_else(|| embed(&appeal), CreateEmbed::from)
+        .field("Decision", &note, false);
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .embed(decided)
+                    .components(vec![]),
+            ),
+        )
+        .await
+        .context("Failed to update appeal")?;
+
+    let verdict = match status {
+        AppealStatus::Approved => "approved",
+        _ => "denied",
+    };
+    if let Err(e) = notify_member(ctx, &appeal, verdict).await {
+        warn!("Could not tell {} about their appeal: {:?}", appeal.name, e);
+    }
+
+    Ok(())
+}
+
+/// Counts the appealed update as accepted. The streak the miss reset is given back in Root, along
+/// with any updates sent since, unless the member missed another day since, which would have
+/// reset it anyway. A correction is posted under the report. Returns the member's streak.
+async fn approve(ctx: &Context, data: &Data, appeal: &mut Appeal) -> anyhow::Result<i32> {
+    let profile = data
+        .profiles
+        .iter()
+        .find(|p| p.name == appeal.profile)
+        .context("The profile of the appeal no longer exists")?;
+    let history = Query::default()
+        .profile(&appeal.profile)
+        .discord_id(&appeal.discord_id)
+        .since(appeal.date)
+        .fetch()
+        .context("Failed to read history")?;
+    let (appealed, since) = history
+        .split_first()
+        .filter(|(o, _)| o.date == appeal.date)
+        .context("The appealed day is no longer in the history")?;
+
+    let mut member = fetch_members()
+        .await
+        .context("Failed to fetch members from Root")?
+        .into_iter()
+        .find(|m| m.discord_id == appeal.discord_id)
+        .context("The member is no longer in Root")?;
+    let restored = appealed.streak_before.max(0) + 1;
+    let target = match appeal.target_streak {
+        Some(target) => target,
+        None => {
+            let current = member.streak.first().map_or(0, |s| s.current_streak);
+            let target = if since.iter().any(|o| o.outcome == Outcome::Missed) {
+                current
+            } else {
+                current + restored - appealed.streak_after
+            };
+            appeal.target_streak = Some(target);
+            appeals::save(appeal).context("Failed to save appeal")?;
+            target
+        }
+    };
+    debug!("Restoring the streak of {} to {}", member.name, target);
+    restore_streak(&mut member, target)
+        .await
+        .context("Failed to restore streak")?;
+
+    let mut outcome = appealed.clone();
+    outcome.outcome = Outcome::Accepted;
+    outcome.message_link = Some(appeal.message_link.clone());
+    outcome.streak_after = restored;
+    history::record(outcome).context("Failed to correct history")?;
+
+    let streak = member.streak.first().map_or(0, |s| s.current_streak);
+    let content = format!(
+        "Correction to the report of {}: {}'s [update]({}) was accepted on appeal. Their streak is \
+         back to {}.",
+        appeal.date, appeal.name, appeal.message_link, streak
+    );
+    let mut message = CreateMessage::new()
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new());
+    let report = runs::get(&profile.name, appeal.date)
+        .context("Failed to read run")?
+        .and_then(|run| run.report_message);
+    if let Some(report) = report {
+        message = message.reference_message((profile.report_channel, report));
+    }
+    profile
+        .report_channel
+        .send_message(&ctx.http, message)
+        .await
+        .context("Failed to post correction")?;
+    appeal.applied = true;
+    appeals::save(appeal).context("Failed to save appeal")?;
+
+    Ok(streak)
+}
+
+async fn notify_member(ctx: &Context, appeal: &Appeal, verdict: &str) -> anyhow::Result<()> {
+    let user_id = UserId::new(
+        appeal
+            .discord_id
+            .parse()
+            .context("Member has an invalid Discord ID")?,
+    );
+    user_id
+        .create_dm_channel(&ctx.http)
+        .await?
+        .send_message(
+            &ctx.http,
+            CreateMessage::new().content(format!(
+                "Your appeal of the report of {} was {}.",
+                appeal.date, verdict
+            )),
+        )
+        .await
+        .context("Failed to DM member")?;
+
+    Ok(())
+}
+
+async fn respond(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    content: &str,
+) -> anyhow::Result<()> {
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+        .context("Failed to respond to button")
+}
+
+fn embed(appeal: &Appeal) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(format!("Appeal - {}", appeal.date))
+        .description(format!(
+            "{} (<@{}>) says [this update]({}) should have counted for the report of {}.",
+            appeal.name, appeal.discord_id, appeal.message_link, appeal.date
+        ))
+        .color(Colour::new(0x3b82f6))
+        .author(discord::author())
+}
+
+fn buttons(id: u64) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}approve:{}", BUTTON_PREFIX, id))
+            .label("Approve")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{}deny:{}", BUTTON_PREFIX, id))
+            .label("Deny")
+            .style(ButtonStyle::Danger),
+    ])]
+}
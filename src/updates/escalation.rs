@@ -0,0 +1,259 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serenity::all::{ChannelId, Context, CreateMessage, RoleId, UserId};
+use tracing::{debug, error, trace};
+
+use super::profile::Profile;
+use crate::storage::{
+    escalations::{self, Escalation},
+    history::{DailyOutcome, Outcome, Query},
+};
+
+/// A step of a profile's escalation ladder, which fires once a member has missed `after` updates
+/// in a row.
+#[derive(Clone, Debug, Deserialize)]
+pub struct EscalationStep {
+    pub after: u32,
+    #[serde(flatten)]
+    pub action: EscalationAction,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum EscalationAction {
+    /// DM the member.
+    Dm,
+    /// DM the mentor of the member's group, or post in the profile's mentors channel if the group
+    /// has none.
+    NotifyMentor,
+    /// Post in an admin channel and optionally give the member a role, which is taken away again
+    /// once they send an update.
+    Admin {
+        channel: ChannelId,
+        #[serde(default)]
+        inactive_role: Option<RoleId>,
+    },
+}
+
+/// Runs the escalation ladder of `profile` for the outcomes recorded on `date`. Steps that already
+/// fired during a member's current run of misses are not repeated.
+pub async fn escalate(ctx: &Context, profile: &Profile, date: NaiveDate) -> anyhow::Result<()> {
+    if profile.escalation.is_empty() {
+        return Ok(());
+    }
+
+    trace!("Running escalations for {}", profile.name);
+    let outcomes = Query::default()
+        .profile(&profile.name)
+        .since(date)
+        .until(date)
+        .fetch()
+        .context("Failed to read today's outcomes")?;
+
+    for outcome in outcomes {
+        let result = match outcome.outcome {
+            Outcome::Accepted | Outcome::Late | Outcome::MadeUp => {
+                de_escalate(ctx, profile, &outcome).await
+            }
+            Outcome::Missed => escalate_member(ctx, profile, &outcome).await,
+            Outcome::Excused | Outcome::Frozen => Ok(()),
+        };
+        if let Err(e) = result {
+            error!("Could not escalate for {}. Error: {:?}", outcome.name, e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn escalate_member(
+    ctx: &Context,
+    profile: &Profile,
+    outcome: &DailyOutcome,
+) -> anyhow::Result<()> {
+    // Root lowers the streak below zero for every further miss, so a streak of 0 is the first.
+    let misses = (1 - outcome.streak_after).max(1) as u32;
+    let fired: Vec<u32> = escalations::active(&profile.name, &outcome.discord_id)?
+        .iter()
+        .map(|e| e.after)
+        .collect();
+
+    for step in &profile.escalation {
+        if step.after > misses || fired.contains(&step.after) {
+            continue;
+        }
+
+        debug!(
+            "Escalating {} after {} misses: {:?}",
+            outcome.name, misses, step.action
+        );
+        // A member with closed DMs must still be escalated to their mentor and the admins.
+        if let Err(e) = run_action(ctx, profile, outcome, misses, &step.action).await {
+            error!(
+                "Could not run {:?} for {}. Error: {:?}",
+                step.action, outcome.name, e
+            );
+            continue;
+        }
+        escalations::record(Escalation {
+            profile: profile.name.clone(),
+            discord_id: outcome.discord_id.clone(),
+            after: step.after,
+            date: outcome.date,
+            resolved: false,
+        })
+        .context("Failed to record escalation")?;
+    }
+
+    Ok(())
+}
+
+async fn run_action(
+    ctx: &Context,
+    profile: &Profile,
+    outcome: &DailyOutcome,
+    misses: u32,
+    action: &EscalationAction,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new(
+        outcome
+            .discord_id
+            .parse()
+            .context("Member has an invalid Discord ID")?,
+    );
+
+    match action {
+        EscalationAction::Dm => {
+            let content = format!(
+                "You've missed {} status updates in a row. If something is keeping you from \
+                 sending them, let your mentor know.",
+                misses
+            );
+            user_id
+                .create_dm_channel(&ctx.http)
+                .await?
+                .send_message(&ctx.http, CreateMessage::new().content(content))
+                .await
+                .context("Failed to DM member")?;
+        }
+        EscalationAction::NotifyMentor => {
+            let content = format!(
+                "{} (<@{}>) has missed {} status updates in a row.",
+                outcome.name, user_id, misses
+            );
+            let mentor = outcome
+                .group_id
+                .and_then(|group| profile.group_mentors.get(&group));
+            if let Some(mentor) = mentor {
+                mentor
+                    .create_dm_channel(&ctx.http)
+                    .await?
+                    .send_message(&ctx.http, CreateMessage::new().content(content))
+                    .await
+                    .context("Failed to DM mentor")?;
+            } else {
+                profile
+                    .mentors_channel
+                    .context("The member's group has no mentor and no mentors channel is set")?
+                    .say(&ctx.http, content)
+                    .await
+                    .context("Failed to notify mentors")?;
+            }
+        }
+        EscalationAction::Admin {
+            channel,
+            inactive_role,
+        } => {
+            channel
+                .say(
+                    &ctx.http,
+                    format!(
+                        "{} (<@{}>) has missed {} status updates in a row.",
+                        outcome.name, user_id, misses
+                    ),
+                )
+                .await
+                .context("Failed to post in the admin channel")?;
+            if let Some(role) = inactive_role {
+                let guild_id = channel
+                    .to_channel(&ctx.http)
+                    .await?
+                    .guild()
+                    .context("The admin channel is not in a server")?
+                    .guild_id;
+                ctx.http
+                    .add_member_role(guild_id, user_id, *role, Some("Missed status updates"))
+                    .await
+                    .context("Failed to assign inactive role")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-arms the ladder for a member who sent an update, taking away the inactive role if it was
+/// given to them. The steps are only resolved once the role is gone, so that a failed removal is
+/// retried with the member's next update.
+async fn de_escalate(
+    ctx: &Context,
+    profile: &Profile,
+    outcome: &DailyOutcome,
+) -> anyhow::Result<()> {
+    let active = escalations::active(&profile.name, &outcome.discord_id)?;
+    if active.is_empty() {
+        return Ok(());
+    }
+
+    debug!("{} is back, resolving escalations", outcome.name);
+    let user_id = UserId::new(
+        outcome
+            .discord_id
+            .parse()
+            .context("Member has an invalid Discord ID")?,
+    );
+    for step in &profile.escalation {
+        let EscalationAction::Admin {
+            channel,
+            inactive_role: Some(role),
+        } = &step.action
+        else {
+            continue;
+        };
+        if !active.iter().any(|e| e.after == step.after) {
+            continue;
+        }
+
+        let guild_id = channel
+            .to_channel(&ctx.http)
+            .await?
+            .guild()
+            .context("The admin channel is not in a server")?
+            .guild_id;
+        ctx.http
+            .remove_member_role(guild_id, user_id, *role, Some("Sent a status update"))
+            .await
+            .context("Failed to remove inactive role")?;
+    }
+    escalations::resolve(&profile.name, &outcome.discord_id)?;
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use serenity::all::{Context, CreateMessage, Message, ReactionType, UserId};
+use tracing::{debug, warn};
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::{
+    profile::Profile,
+    rules::{Evaluation, Rule},
+};
+
+const ACCEPTED_EMOJI: &str = "✅";
+const NEAR_MISS_EMOJI: &str = "⚠️";
+/// How long a fallback reply stays in the channel before it is cleaned up.
+const REPLY_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Tracks when each member was last told about a near-miss so that repeated edits don't spam them.
+pub struct Feedback {
+    cooldown: Duration,
+    last_notice: Mutex<HashMap<UserId, Instant>>,
+}
+
+impl Feedback {
+    /// Reads the cooldown from `FEEDBACK_COOLDOWN_MINUTES`, defaulting to 30 minutes.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let minutes = match std::env::var("FEEDBACK_COOLDOWN_MINUTES") {
+            Ok(value) => value
+                .parse()
+                .context("Failed to parse FEEDBACK_COOLDOWN_MINUTES")?,
+            Err(_) => 30,
+        };
+
+        Ok(Self {
+            cooldown: Duration::from_secs(minutes * 60),
+            last_notice: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns `true` and restarts the cooldown if `user_id` may be notified again.
+    fn try_start_cooldown(&self, user_id: UserId) -> bool {
+        let mut last_notice = self.last_notice.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match last_notice.get(&user_id) {
+            Some(&last) if now.duration_since(last) < self.cooldown => false,
+            _ => {
+                last_notice.insert(user_id, now);
+                true
+            }
+        }
+    }
+}
+
+/// Reacts to `msg` according to `evaluation` and tells the author about near-misses.
+/// `is_edit` indicates that `msg` may already carry reactions from an earlier evaluation.
+pub async fn give_feedback(
+    ctx: &Context,
+    feedback: &Feedback,
+    profile: &Profile,
+    msg: &Message,
+    evaluation: &Evaluation,
+    is_edit: bool,
+) -> anyhow::Result<()> {
+    let accepted = ReactionType::Unicode(ACCEPTED_EMOJI.to_string());
+    let near_miss = ReactionType::Unicode(NEAR_MISS_EMOJI.to_string());
+
+    if evaluation.is_accepted() {
+        if is_edit {
+            msg.delete_reaction(&ctx.http, None, near_miss).await?;
+        }
+        msg.react(&ctx.http, accepted).await?;
+    } else if evaluation.is_near_miss() {
+        if is_edit {
+            msg.delete_reaction(&ctx.http, None, accepted).await?;
+        }
+        msg.react(&ctx.http, near_miss).await?;
+
+        if feedback.try_start_cooldown(msg.author.id) {
+            send_notice(ctx, profile, msg, evaluation).await?;
+        } else {
+            debug!("Skipping notice for {}, still on cooldown", msg.author.name);
+        }
+    } else if is_edit {
+        msg.delete_reaction(&ctx.http, None, accepted).await?;
+        msg.delete_reaction(&ctx.http, None, near_miss).await?;
+    }
+
+    Ok(())
+}
+
+/// DMs the author the rules their update failed. If their DMs are closed, replies in the channel
+/// instead and deletes the reply after [`REPLY_LIFETIME`].
+async fn send_notice(
+    ctx: &Context,
+    profile: &Profile,
+    msg: &Message,
+    evaluation: &Evaluation,
+) -> anyhow::Result<()> {
+    let mut content = format!(
+        "Your status update in {} won't be counted yet:\n",
+        msg.link()
+    );
+    for rule in &evaluation.failed {
+        content.push_str(&format!("- {}\n", rule.description(profile)));
+    }
+    // Editing can't move an update into the window, whose rule says what to do instead.
+    if evaluation.failed.iter().any(|rule| *rule != Rule::Window) {
+        content.push_str("Edit your message to fix it.");
+    }
+
+    if msg
+        .author
+        .direct_message(&ctx.http, CreateMessage::new().content(&content))
+        .await
+        .is_ok()
+    {
+        return Ok(());
+    }
+
+    warn!("Could not DM {}, replying instead", msg.author.name);
+    let reply = msg
+        .reply_ping(&ctx.http, content)
+        .await
+        .context("Failed to reply with near-miss notice")?;
+    let http = ctx.http.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(REPLY_LIFETIME).await;
+        if let Err(e) = reply.delete(&http).await {
+            warn!(
+                "Could not delete near-miss notice {}. Error: {}",
+                reply.id, e
+            );
+        }
+    });
+
+    Ok(())
+}
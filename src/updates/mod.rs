@@ -0,0 +1,231 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+/// Members' appeals of missed updates, which mentors approve or deny.
+pub mod appeals;
+/// Follow-ups for members who keep missing their updates.
+pub mod escalation;
+/// Reactions and notices that tell members whether their update counted.
+pub mod feedback;
+/// Mentors' decisions to count or reject messages regardless of the rules.
+pub mod overrides;
+/// Extracts the sections of the update template from the content of an update.
+pub mod parser;
+/// Named, independently scheduled sets of expectations for cohorts of members.
+pub mod profile;
+/// The rules a message must satisfy to count as a status update.
+pub mod rules;
+/// Detection of updates that repeat the author's earlier ones or copy someone else's.
+pub mod similarity;
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serenity::all::{
+    ChannelId, Context, GetMessages, GuildId, Message, MessageId, MessageUpdateEvent, RoleId,
+    UserId,
+};
+use tracing::{debug, trace};
+
+use crate::{
+    storage::{
+        self,
+        updates::{self, RecordedUpdate},
+    },
+    Data,
+};
+use profile::Profile;
+
+/// Returns the profile whose update channels include `channel_id`, if any.
+fn profile_for(data: &Data, channel_id: ChannelId) -> Option<&Profile> {
+    data.profiles
+        .iter()
+        .find(|p| p.channels.contains(&channel_id))
+}
+
+/// Returns whether `user_id` has the role in `MENTOR_ROLE_ID`. Nobody is a mentor if it isn't set.
+pub async fn is_mentor(ctx: &Context, guild_id: GuildId, user_id: UserId) -> anyhow::Result<bool> {
+    trace!("Checking whether {} is a mentor", user_id);
+    let role_id = std::env::var("MENTOR_ROLE_ID").unwrap_or_default();
+    if role_id.trim().is_empty() {
+        return Ok(false);
+    }
+    let role_id: u64 = role_id
+        .trim()
+        .parse()
+        .context("Failed to parse MENTOR_ROLE_ID")?;
+    let member = guild_id
+        .member(ctx, user_id)
+        .await
+        .context("Failed to fetch member")?;
+
+    Ok(member.roles.contains(&RoleId::new(role_id)))
+}
+
+/// Records the updates posted in the channels of every profile since its current window opened,
+/// so that updates sent while amD was offline still count. No feedback is given on them.
+pub async fn backfill(ctx: &Context, data: &Data) -> anyhow::Result<()> {
+    for profile in &data.profiles {
+        let start = profile.schedule.current_window().start;
+        trace!("Backfilling updates of {} since {}", profile.name, start);
+        let mut backfilled = vec![];
+        for channel_id in &profile.channels {
+            let guild_id = channel_id
+                .to_channel(&ctx.http)
+                .await
+                .with_context(|| format!("Failed to fetch channel {}", channel_id))?
+                .guild()
+                .map(|channel| channel.guild_id);
+
+            let mut after = message_id_at(start.to_utc());
+            loop {
+                let messages = channel_id
+                    .messages(&ctx.http, GetMessages::new().after(after).limit(100))
+                    .await
+                    .with_context(|| format!("Failed to get messages from {}", channel_id))?;
+                let Some(latest) = messages.iter().map(|m| m.id).max() else {
+                    break;
+                };
+                after = latest;
+
+                for mut msg in messages {
+                    // Messages fetched over HTTP don't carry the guild they were sent in.
+                    msg.guild_id = msg.guild_id.or(guild_id);
+                    if !msg.author.bot && rules::evaluate(&msg, profile).is_accepted() {
+                        backfilled.push(RecordedUpdate {
+                            parsed: parser::parse(&msg.content, &profile.rules),
+                            ..RecordedUpdate::from(&msg)
+                        });
+                    }
+                }
+            }
+        }
+
+        debug!(
+            "Backfilled {} updates of {}",
+            backfilled.len(),
+            profile.name
+        );
+        updates::record_all(backfilled).context("Failed to record backfilled updates")?;
+    }
+
+    Ok(())
+}
+
+/// Returns the smallest ID a message sent at `time` can have, for paging through history.
+fn message_id_at(time: DateTime<Utc>) -> MessageId {
+    const DISCORD_EPOCH_MS: i64 = 1_420_070_400_000;
+    let ms = (time.timestamp_millis() - DISCORD_EPOCH_MS).max(1) as u64;
+    MessageId::new(ms << 22)
+}
+
+/// Evaluates a newly posted message and records it if it is a valid status update.
+pub async fn handle_message(ctx: &Context, data: &Data, msg: &Message) -> anyhow::Result<()> {
+    let Some(profile) = profile_for(data, msg.channel_id) else {
+        return Ok(());
+    };
+    if msg.author.bot {
+        return Ok(());
+    }
+
+    trace!("Evaluating message {} from {}", msg.id, msg.author.name);
+    evaluate_and_record(ctx, data, profile, msg, false).await
+}
+
+/// Re-evaluates an edited message, since an edit can both fix and break an update.
+pub async fn handle_message_update(
+    ctx: &Context,
+    data: &Data,
+    new: Option<&Message>,
+    event: &MessageUpdateEvent,
+) -> anyhow::Result<()> {
+    let Some(profile) = profile_for(data, event.channel_id) else {
+        return Ok(());
+    };
+
+    let mut msg = match new {
+        Some(msg) => msg.clone(),
+        None => event
+            .channel_id
+            .message(&ctx.http, event.id)
+            .await
+            .with_context(|| format!("Failed to fetch edited message {}", event.id))?,
+    };
+
+    if msg.author.bot {
+        return Ok(());
+    }
+    // Messages fetched over HTTP don't carry the guild they were sent in.
+    msg.guild_id = msg.guild_id.or(event.guild_id);
+
+    trace!(
+        "Re-evaluating edited message {} from {}",
+        msg.id,
+        msg.author.name
+    );
+    evaluate_and_record(ctx, data, profile, &msg, true).await
+}
+
+/// Forgets a deleted message so that it no longer counts as an update.
+pub fn handle_message_delete(
+    data: &Data,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> anyhow::Result<()> {
+    if profile_for(data, channel_id).is_none() {
+        return Ok(());
+    }
+
+    if updates::remove(message_id).context("Failed to remove deleted update")? {
+        debug!("Removed deleted update {}", message_id);
+    }
+    if storage::overrides::remove(message_id, None).context("Failed to remove override")? {
+        debug!("Removed override of deleted message {}", message_id);
+    }
+
+    Ok(())
+}
+
+async fn evaluate_and_record(
+    ctx: &Context,
+    data: &Data,
+    profile: &Profile,
+    msg: &Message,
+    is_edit: bool,
+) -> anyhow::Result<()> {
+    let evaluation = rules::evaluate(msg, profile);
+    debug!("Evaluation of {}: {:?}", msg.id, evaluation);
+    if evaluation.is_accepted() {
+        debug!("Recording update {} from {}", msg.id, msg.author.name);
+        let update = RecordedUpdate {
+            parsed: parser::parse(&msg.content, &profile.rules),
+            ..RecordedUpdate::from(msg)
+        };
+        if update.parsed.is_empty() {
+            debug!("Update {} doesn't follow the template", msg.id);
+        } else {
+            trace!("Parsed update {}: {:?}", msg.id, update.parsed);
+        }
+        updates::record(update).context("Failed to record update")?;
+    } else if is_edit {
+        // Only an edit can invalidate a message that was recorded.
+        updates::remove(msg.id).context("Failed to remove invalidated update")?;
+    }
+
+    feedback::give_feedback(ctx, &data.feedback, profile, msg, &evaluation, is_edit)
+        .await
+        .context("Failed to give feedback on update")
+}
@@ -0,0 +1,131 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use serenity::all::{Context, Message, Reaction, ReactionType, User};
+use tracing::debug;
+
+use super::{is_mentor, parser, profile_for, rules};
+use crate::{
+    storage::{
+        overrides::{self, Override, Verdict},
+        updates::RecordedUpdate,
+    },
+    Data,
+};
+
+/// Reaction with which a mentor counts a message as a status update. Unlike ✅, which amD marks
+/// accepted updates with, or 👍, it isn't a reaction anyone adds in passing.
+const ACCEPT_EMOJI: &str = "🆗";
+/// Reaction with which a mentor rejects a message that would otherwise count.
+const REJECT_EMOJI: &str = "❌";
+
+/// Records or withdraws a mentor's decision on a message in an update channel when they add or
+/// remove one of the override reactions. Reactions by anyone else are ignored.
+pub async fn handle_reaction(
+    ctx: &Context,
+    data: &Data,
+    reaction: &Reaction,
+    is_add: bool,
+) -> anyhow::Result<()> {
+    if profile_for(data, reaction.channel_id).is_none() {
+        return Ok(());
+    }
+    let verdict = match &reaction.emoji {
+        ReactionType::Unicode(emoji) if emoji == ACCEPT_EMOJI => Verdict::Accept,
+        ReactionType::Unicode(emoji) if emoji == REJECT_EMOJI => Verdict::Reject,
+        _ => return Ok(()),
+    };
+    let (Some(guild_id), Some(user_id)) = (reaction.guild_id, reaction.user_id) else {
+        return Ok(());
+    };
+    if user_id == ctx.cache.current_user().id || !is_mentor(ctx, guild_id, user_id).await? {
+        return Ok(());
+    }
+
+    if !is_add {
+        if overrides::remove(reaction.message_id, Some(verdict))? {
+            debug!(
+                "{} withdrew their override of {}",
+                user_id, reaction.message_id
+            );
+        }
+        return Ok(());
+    }
+
+    let mut msg = reaction
+        .message(&ctx.http)
+        .await
+        .context("Failed to fetch overridden message")?;
+    // Messages fetched over HTTP don't carry the guild they were sent in.
+    msg.guild_id = msg.guild_id.or(Some(guild_id));
+    let mentor = user_id
+        .to_user(&ctx.http)
+        .await
+        .context("Failed to fetch mentor")?;
+    decide(data, &msg, verdict, &mentor)?;
+
+    Ok(())
+}
+
+/// What came of a mentor's decision on a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Recorded,
+    /// The message isn't in an update channel.
+    NotAnUpdate,
+    /// The message already counts, so accepting it would only clutter the report.
+    AlreadyCounts,
+}
+
+/// Records `mentor`'s `verdict` on `msg`, which the next report honors regardless of the rules.
+pub fn decide(
+    data: &Data,
+    msg: &Message,
+    verdict: Verdict,
+    mentor: &User,
+) -> anyhow::Result<Decision> {
+    let Some(profile) = profile_for(data, msg.channel_id) else {
+        return Ok(Decision::NotAnUpdate);
+    };
+    // Accepting still matters for updates that follow the rules if similar ones are rejected.
+    if verdict == Verdict::Accept
+        && !profile.rules.reject_similar
+        && rules::evaluate(msg, profile).is_accepted()
+    {
+        debug!("Message {} already counts, not overriding it", msg.id);
+        return Ok(Decision::AlreadyCounts);
+    }
+
+    debug!(
+        "{} overrode message {} from {}: {:?}",
+        mentor.name, msg.id, msg.author.name, verdict
+    );
+    let update = RecordedUpdate {
+        parsed: parser::parse(&msg.content, &profile.rules),
+        ..RecordedUpdate::from(msg)
+    };
+    overrides::set(Override {
+        update,
+        verdict,
+        mentor_id: mentor.id,
+        mentor_name: mentor.name.clone(),
+    })
+    .context("Failed to record override")?;
+
+    Ok(Decision::Recorded)
+}
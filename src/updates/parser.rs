@@ -0,0 +1,409 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::{Deserialize, Serialize};
+
+use super::rules::RuleSet;
+
+/// The sections of the update template, as extracted by [`parse`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusUpdate {
+    /// What the member did since their last update.
+    pub done: Vec<String>,
+    /// What the member plans to do next.
+    pub planned: Vec<String>,
+    /// Anything the member is stuck on. Entries such as "None" are dropped.
+    pub blockers: Vec<String>,
+}
+
+impl StatusUpdate {
+    /// Returns whether none of the sections could be found.
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty() && self.planned.is_empty() && self.blockers.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    Done,
+    Planned,
+    Blockers,
+}
+
+/// Headings that start each section, after normalization. Headings may also continue past these,
+/// as in "What I did today".
+const SYNONYMS: [(Section, &[&str]); 3] = [
+    (
+        Section::Done,
+        &[
+            "what i did",
+            "what i have done",
+            "what ive done",
+            "what i worked on",
+            "done",
+            "did",
+            "completed",
+            "accomplished",
+            "achievements",
+            "progress",
+            "work done",
+            "today",
+            "yesterday",
+        ],
+    ),
+    (
+        Section::Planned,
+        &[
+            "what ill do",
+            "what i will do",
+            "what im going to do",
+            "what i plan to do",
+            "to do",
+            "todo",
+            "plan",
+            "plans",
+            "planned",
+            "next",
+            "next steps",
+            "whats next",
+            "tomorrow",
+            "goals",
+        ],
+    ),
+    (
+        Section::Blockers,
+        &[
+            "blockers",
+            "blocker",
+            "blocked",
+            "blocking",
+            "issues",
+            "problems",
+            "challenges",
+            "difficulties",
+            "impediments",
+            "roadblocks",
+            "stuck",
+        ],
+    ),
+];
+
+/// Synonyms that are just as likely to start an entry, as in "Problems: solved 5 leetcode
+/// problems". They only start a section inline on a line that isn't an entry of another section.
+const AMBIGUOUS: [&str; 14] = [
+    "today",
+    "yesterday",
+    "progress",
+    "completed",
+    "next",
+    "plan",
+    "plans",
+    "goals",
+    "tomorrow",
+    "issues",
+    "problems",
+    "challenges",
+    "difficulties",
+    "stuck",
+];
+
+/// Entries that mean the section is empty, after normalization.
+const NOTHING: [&str; 8] = [
+    "none",
+    "nil",
+    "na",
+    "nothing",
+    "no",
+    "no blockers",
+    "no issues",
+    "nothing as of now",
+];
+
+/// Words that may follow an entry from [`NOTHING`], as in "None so far".
+const QUALIFIERS: [&str; 8] = [
+    "so far",
+    "as of now",
+    "for now",
+    "yet",
+    "right now",
+    "at the moment",
+    "currently",
+    "today",
+];
+
+/// Longest heading, in words, that is recognized when followed by content on the same line.
+const MAX_INLINE_HEADING_WORDS: usize = 5;
+
+/// Extracts the done, planned and blocker sections from the content of an update. Lines before the
+/// first recognized heading, such as the greeting, are ignored, as is everything from the sign-off
+/// of `rules` onwards.
+pub fn parse(content: &str, rules: &RuleSet) -> StatusUpdate {
+    let sign_off = rules.sign_off.as_ref().map(|s| s.to_lowercase());
+    let mut update = StatusUpdate::default();
+    let mut current = None;
+
+    for line in content.lines() {
+        if sign_off
+            .as_ref()
+            .is_some_and(|s| line.to_lowercase().contains(s))
+        {
+            break;
+        }
+
+        let (text, is_marked, is_item) = strip_markers(line);
+        if text.is_empty() {
+            continue;
+        }
+
+        let in_list = is_item && current.is_some();
+        match heading(text, is_marked, in_list) {
+            Some((section, rest)) => {
+                current = Some(section);
+                if let Some(rest) = rest {
+                    push(&mut update, section, rest);
+                }
+            }
+            None => {
+                if let Some(section) = current {
+                    push(&mut update, section, text);
+                }
+            }
+        }
+    }
+
+    update
+}
+
+/// Removes markdown headings, quotes, bullets, numbering and emphasis from `line`. Also returns
+/// whether the line was a markdown heading or entirely emphasized, which makes it a heading
+/// candidate on its own, and whether it was a list item.
+fn strip_markers(line: &str) -> (&str, bool, bool) {
+    let mut text = line.trim();
+    let mut is_marked = false;
+    let mut is_item = false;
+
+    if let Some(rest) = text.strip_prefix('>') {
+        text = rest.trim_start();
+    }
+    if text.starts_with('#') {
+        text = text.trim_start_matches('#').trim_start();
+        is_marked = true;
+    }
+    for bullet in ["- ", "* ", "+ ", "• "] {
+        if let Some(rest) = text.strip_prefix(bullet) {
+            text = rest.trim_start();
+            is_item = true;
+            break;
+        }
+    }
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        if let Some(rest) = text[digits..]
+            .strip_prefix(". ")
+            .or_else(|| text[digits..].strip_prefix(") "))
+        {
+            text = rest.trim_start();
+            is_item = true;
+        }
+    }
+    for emphasis in ["**", "__", "*", "_"] {
+        if let Some(inner) = text
+            .strip_prefix(emphasis)
+            .and_then(|t| t.strip_suffix(emphasis))
+        {
+            text = inner.trim();
+            is_marked = true;
+            break;
+        }
+    }
+
+    (text, is_marked, is_item)
+}
+
+/// Recognizes `text` as the heading of a section, returning the section and any content that
+/// follows the heading on the same line. `in_list` indicates that `text` is an item of a list in
+/// a section, where [`AMBIGUOUS`] labels are taken as content.
+fn heading(text: &str, is_marked: bool, in_list: bool) -> Option<(Section, Option<&str>)> {
+    // "Blockers: none" and "**Done:** fixed the login page"
+    if let Some((label, rest)) = text.split_once(':') {
+        let label = normalize(label);
+        let is_ambiguous = AMBIGUOUS
+            .iter()
+            .any(|a| label == *a || label.strip_prefix(a).is_some_and(|r| r.starts_with(' ')));
+        if label.split(' ').count() <= MAX_INLINE_HEADING_WORDS && !(in_list && is_ambiguous) {
+            if let Some(section) = section_for(&label, true) {
+                let rest = rest.trim().trim_start_matches(['*', '_']).trim();
+                return Some((section, (!rest.is_empty()).then_some(rest)));
+            }
+        }
+    }
+
+    // A heading on a line of its own. Plain lines must match a synonym exactly, so that an entry
+    // such as "Done with the login page" isn't mistaken for a heading.
+    section_for(&normalize(text), is_marked).map(|section| (section, None))
+}
+
+fn section_for(label: &str, allow_prefix: bool) -> Option<Section> {
+    SYNONYMS.iter().find_map(|(section, synonyms)| {
+        synonyms
+            .iter()
+            .any(|synonym| {
+                label == *synonym
+                    || (allow_prefix
+                        && label
+                            .strip_prefix(synonym)
+                            .is_some_and(|rest| rest.starts_with(' ')))
+            })
+            .then_some(*section)
+    })
+}
+
+/// Lowercases `text` and drops everything but letters, digits and single spaces, so that
+/// "What I'll do?" and "what ill do" compare equal.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn push(update: &mut StatusUpdate, section: Section, entry: &str) {
+    if is_nothing(entry) {
+        return;
+    }
+
+    let entries = match section {
+        Section::Done => &mut update.done,
+        Section::Planned => &mut update.planned,
+        Section::Blockers => &mut update.blockers,
+    };
+    entries.push(entry.to_string());
+}
+
+/// Returns whether `entry` means the section is empty, such as "None" or "Nothing as of now".
+fn is_nothing(entry: &str) -> bool {
+    let entry = normalize(entry);
+    let unqualified = QUALIFIERS
+        .iter()
+        .find_map(|q| entry.strip_suffix(q).map(str::trim_end))
+        .unwrap_or(&entry);
+
+    NOTHING.contains(&entry.as_str()) || NOTHING.contains(&unqualified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_default(content: &str) -> StatusUpdate {
+        parse(content, &RuleSet::default())
+    }
+
+    #[test]
+    fn parses_markdown_headings_and_bullets() {
+        let update = parse_default(
+            "Namah Shivaya\n\
+             ## What I did today\n\
+             - Fixed the login page\n\
+             - Reviewed a PR\n\
+             ## What I'll do tomorrow\n\
+             1. Write tests\n\
+             ## Blockers\n\
+             - None\n\
+             Regards",
+        );
+
+        assert_eq!(update.done, ["Fixed the login page", "Reviewed a PR"]);
+        assert_eq!(update.planned, ["Write tests"]);
+        assert!(update.blockers.is_empty());
+    }
+
+    #[test]
+    fn parses_inline_and_emphasized_labels() {
+        let update = parse_default(
+            "**Done:** fixed the navbar\n\
+             *Plans*\n\
+             * add dark mode\n\
+             Blockers: waiting on API access",
+        );
+
+        assert_eq!(update.done, ["fixed the navbar"]);
+        assert_eq!(update.planned, ["add dark mode"]);
+        assert_eq!(update.blockers, ["waiting on API access"]);
+    }
+
+    #[test]
+    fn parses_bulleted_inline_labels() {
+        let update = parse_default(
+            "- What I did: set up CI\n\
+             - What I'll do: deploy it\n\
+             - Blockers: none",
+        );
+
+        assert_eq!(update.done, ["set up CI"]);
+        assert_eq!(update.planned, ["deploy it"]);
+        assert!(update.blockers.is_empty());
+    }
+
+    #[test]
+    fn keeps_ambiguous_labels_in_lists_as_entries() {
+        let update = parse_default(
+            "Done\n\
+             - Problems: solved 5 leetcode problems\n\
+             - Progress: finished chapter 3\n\
+             Blockers\n\
+             - Issues: the build is flaky",
+        );
+
+        assert_eq!(
+            update.done,
+            [
+                "Problems: solved 5 leetcode problems",
+                "Progress: finished chapter 3"
+            ]
+        );
+        assert_eq!(update.blockers, ["Issues: the build is flaky"]);
+    }
+
+    #[test]
+    fn drops_qualified_nothing_entries() {
+        for entry in ["None so far", "Nothing as of now", "No blockers yet", "nil"] {
+            let update = parse_default(&format!("Blockers: {}", entry));
+            assert!(update.blockers.is_empty(), "{} was kept", entry);
+        }
+    }
+
+    #[test]
+    fn ignores_greeting_sign_off_and_plain_entries() {
+        let update = parse_default(
+            "Namah Shivaya\n\
+             Done with the login page\n\
+             What I did\n\
+             Done with the login page\n\
+             Regards\n\
+             Blockers: none of this counts",
+        );
+
+        assert_eq!(update.done, ["Done with the login page"]);
+        assert!(update.blockers.is_empty());
+        assert!(parse_default("Just chatting").is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use chrono::Weekday;
+use serde::Deserialize;
+use serenity::all::{ChannelId, UserId};
+use tracing::{debug, warn};
+
+use std::collections::HashMap;
+
+use super::{escalation::EscalationStep, rules::RuleSet};
+use crate::{
+    graphql::models::Member,
+    ids::{
+        GROUP_FOUR_CHANNEL_ID, GROUP_ONE_CHANNEL_ID, GROUP_THREE_CHANNEL_ID, GROUP_TWO_CHANNEL_ID,
+        STATUS_UPDATE_CHANNEL_ID,
+    },
+    report::discord::{GroupBreakdown, LinkMode, MentionPolicy},
+    utils::time::Schedule,
+};
+
+/// A named set of expectations for a cohort of members, such as first-years or seniors. Every
+/// profile gets its own scheduled tasks.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Only members in one of these Root groups are tracked. Empty means every group.
+    #[serde(default)]
+    pub groups: Vec<i32>,
+    /// Only members in one of these years are tracked. Empty means every year.
+    #[serde(default)]
+    pub years: Vec<i32>,
+    /// Names of members who are never tracked.
+    #[serde(default)]
+    pub excluded: Vec<String>,
+    /// The channels updates are posted in.
+    pub channels: Vec<ChannelId>,
+    /// The channel the reports are posted to.
+    pub report_channel: ChannelId,
+    /// The channel the daily digest of blockers is posted to. `None` disables the digest.
+    #[serde(default)]
+    pub mentors_channel: Option<ChannelId>,
+    #[serde(default)]
+    pub rules: RuleSet,
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// Days of the week that are never evaluated.
+    #[serde(default)]
+    pub skip_weekdays: Vec<Weekday>,
+    /// Days of the week on which updates are optional.
+    #[serde(default)]
+    pub optional_weekdays: Vec<Weekday>,
+    /// `None` disables streak freezes.
+    #[serde(default)]
+    pub freezes: Option<FreezePolicy>,
+    /// What happens when members keep missing updates, in any order.
+    #[serde(default)]
+    pub escalation: Vec<EscalationStep>,
+    /// How the report refers to and pings members.
+    #[serde(default)]
+    pub mentions: MentionPolicy,
+    /// Whether the report links to each accepted update.
+    #[serde(default)]
+    pub links: LinkMode,
+    /// Whether the report is broken down by Root group, and where each group's numbers go.
+    #[serde(default)]
+    pub breakdown: GroupBreakdown,
+    /// The mentor of each Root group, who is notified by the `notify_mentor` escalation step.
+    #[serde(default)]
+    pub group_mentors: HashMap<i32, UserId>,
+}
+
+/// Members earn a token for every `every` consecutive updates, and hold at most `cap` of them. A
+/// token is spent automatically to keep a streak alive when an update is missed.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FreezePolicy {
+    pub every: u32,
+    pub cap: u32,
+}
+
+impl FreezePolicy {
+    /// Reads the policy from `FREEZE_EVERY` and `FREEZE_CAP`. Freezes are disabled if
+    /// `FREEZE_EVERY` isn't set.
+    fn from_env() -> anyhow::Result<Option<Self>> {
+        let every = match std::env::var("FREEZE_EVERY") {
+            Ok(every) if !every.is_empty() => {
+                every.parse().context("Failed to parse FREEZE_EVERY")?
+            }
+            _ => return Ok(None),
+        };
+        let cap = match std::env::var("FREEZE_CAP") {
+            Ok(cap) if !cap.is_empty() => cap.parse().context("Failed to parse FREEZE_CAP")?,
+            _ => 1,
+        };
+
+        Ok(Some(Self { every, cap }))
+    }
+}
+
+impl Profile {
+    /// Returns whether `member` is expected to send status updates under this profile.
+    pub fn includes(&self, member: &Member) -> bool {
+        !self.excluded.contains(&member.name)
+            && (self.groups.is_empty() || member.group_id.is_some_and(|g| self.groups.contains(&g)))
+            && (self.years.is_empty() || member.year.is_some_and(|y| self.years.contains(&y)))
+    }
+}
+
+/// Loads the profiles from the JSON file at `PROFILES_FILE_NAME`. If it isn't set, a single
+/// profile covering every group channel is built from the ENV instead.
+pub fn load_profiles() -> anyhow::Result<Vec<Profile>> {
+    let file_name = match std::env::var("PROFILES_FILE_NAME") {
+        Ok(file_name) if !file_name.is_empty() => file_name,
+        _ => {
+            let profile = default_profile()?;
+            warn_if_unseen(&profile);
+            return Ok(vec![profile]);
+        }
+    };
+
+    let content = std::fs::read_to_string(&file_name)
+        .with_context(|| format!("Failed to read profiles from {}", file_name))?;
+    let profiles: Vec<Profile> =
+        serde_json::from_str(&content).context("Failed to parse profiles")?;
+
+    debug!("Profiles: {:?}", profiles);
+    for profile in &profiles {
+        warn_if_unseen(profile);
+    }
+    Ok(profiles)
+}
+
+/// Warns if `profile` flags similar updates without a mentors channel for the flags to go to.
+/// They are then only listed in reports exported with `/report`.
+fn warn_if_unseen(profile: &Profile) {
+    if profile.rules.similarity_threshold.is_some() && profile.mentors_channel.is_none() {
+        warn!(
+            "Profile {} checks updates for similarity but has no mentors channel",
+            profile.name
+        );
+    }
+}
+
+// TOOD: Get IDs through ENV instead
+fn default_profile() -> anyhow::Result<Profile> {
+    Ok(Profile {
+        name: "default".to_string(),
+        groups: vec![],
+        years: vec![],
+        excluded: vec!["Pakhi Banchalia".to_string()],
+        channels: vec![
+            ChannelId::new(GROUP_ONE_CHANNEL_ID),
+            ChannelId::new(GROUP_TWO_CHANNEL_ID),
+            ChannelId::new(GROUP_THREE_CHANNEL_ID),
+            ChannelId::new(GROUP_FOUR_CHANNEL_ID),
+        ],
+        report_channel: ChannelId::new(STATUS_UPDATE_CHANNEL_ID),
+        mentors_channel: mentors_channel_from_env()?,
+        rules: RuleSet::default(),
+        schedule: Schedule::from_env()?,
+        skip_weekdays: vec![],
+        optional_weekdays: vec![],
+        freezes: FreezePolicy::from_env()?,
+        escalation: vec![],
+        group_mentors: HashMap::new(),
+        mentions: MentionPolicy::default(),
+        links: LinkMode::default(),
+        breakdown: GroupBreakdown::default(),
+    })
+}
+
+/// Reads the channel for the blocker digest from `MENTORS_CHANNEL_ID`, if it is set.
+fn mentors_channel_from_env() -> anyhow::Result<Option<ChannelId>> {
+    match std::env::var("MENTORS_CHANNEL_ID") {
+        Ok(id) if !id.is_empty() => Ok(Some(ChannelId::new(
+            id.parse().context("Failed to parse MENTORS_CHANNEL_ID")?,
+        ))),
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,147 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::Deserialize;
+use serenity::all::Message;
+
+use super::profile::Profile;
+
+/// The phrases a profile requires in its updates. Phrases are matched case-insensitively.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    /// `None` disables [`Rule::Greeting`].
+    pub greeting: Option<String>,
+    /// `None` disables [`Rule::SignOff`].
+    pub sign_off: Option<String>,
+    /// Usernames that don't need to send the greeting.
+    pub greeting_exempt: Vec<String>,
+    /// Marks an update as a make-up for the previous day. `None` disables make-up updates.
+    pub makeup_tag: Option<String>,
+    /// Updates at least this similar to a recent one are flagged to mentors. `None` disables the
+    /// check.
+    pub similarity_threshold: Option<f64>,
+    /// Don't count flagged updates at all, instead of only flagging them.
+    pub reject_similar: bool,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            greeting: Some("Namah Shivaya".to_string()),
+            sign_off: Some("Regards".to_string()),
+            greeting_exempt: vec!["amanoslean".to_string()],
+            makeup_tag: Some("#makeup".to_string()),
+            similarity_threshold: Some(0.8),
+            reject_similar: false,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Returns whether `content` is tagged as a make-up update.
+    pub fn is_makeup(&self, content: &str) -> bool {
+        self.makeup_tag
+            .as_ref()
+            .is_some_and(|tag| content.to_lowercase().contains(&tag.to_lowercase()))
+    }
+}
+
+/// A single requirement of a status update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    Greeting,
+    SignOff,
+    /// The update must be sent while a collection window, or its grace period, is open.
+    Window,
+}
+
+impl Rule {
+    /// A short explanation of the rule, suitable for showing to the author of a failed update.
+    pub fn description(&self, profile: &Profile) -> String {
+        let rules = &profile.rules;
+        match self {
+            Rule::Greeting => format!(
+                "Start your update with \"{}\".",
+                rules.greeting.as_deref().unwrap_or_default()
+            ),
+            Rule::SignOff => format!(
+                "End your update with \"{}\".",
+                rules.sign_off.as_deref().unwrap_or_default()
+            ),
+            Rule::Window => format!(
+                "Send your update between {} and {} ({}). Post it again once the window opens.",
+                profile.schedule.window_start.format("%H:%M"),
+                profile.schedule.window_end.format("%H:%M"),
+                profile.schedule.timezone
+            ),
+        }
+    }
+}
+
+/// The outcome of checking a message against every [`Rule`].
+#[derive(Clone, Debug, Default)]
+pub struct Evaluation {
+    pub passed: Vec<Rule>,
+    pub failed: Vec<Rule>,
+}
+
+impl Evaluation {
+    pub fn is_accepted(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// A near-miss satisfies some, but not all, of the rules. Messages that satisfy none of the
+    /// content rules are most likely regular conversation and are not treated as attempted updates.
+    pub fn is_near_miss(&self) -> bool {
+        !self.failed.is_empty() && !self.passed.is_empty()
+    }
+}
+
+/// Checks `msg` against every [`Rule`] enabled in the rules of `profile`, and against its schedule.
+pub fn evaluate(msg: &Message, profile: &Profile) -> Evaluation {
+    let rules = &profile.rules;
+    let content = msg.content.to_lowercase();
+    let contains = |phrase: &str| content.contains(&phrase.to_lowercase());
+    let mut evaluation = Evaluation::default();
+
+    let mut checks = vec![];
+    if let Some(greeting) = &rules.greeting {
+        if !rules.greeting_exempt.contains(&msg.author.name) {
+            checks.push((Rule::Greeting, contains(greeting)));
+        }
+    }
+    if let Some(sign_off) = &rules.sign_off {
+        checks.push((Rule::SignOff, contains(sign_off)));
+    }
+    for (rule, passed) in checks {
+        if passed {
+            evaluation.passed.push(rule);
+        } else {
+            evaluation.failed.push(rule);
+        }
+    }
+
+    // Otherwise the update would look accepted but never be counted by any report. Being sent in
+    // the window is only ever a reason to reject a message, so that regular conversation during
+    // the window isn't mistaken for a near-miss.
+    if profile.schedule.report_date_of(*msg.timestamp).is_none() {
+        evaluation.failed.push(Rule::Window);
+    }
+
+    evaluation
+}
@@ -0,0 +1,187 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+
+use super::rules::RuleSet;
+use crate::storage::updates::RecordedUpdate;
+
+/// Number of consecutive words in a shingle.
+const SHINGLE_SIZE: usize = 3;
+
+/// An update that is suspiciously similar to an earlier one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimilarUpdate {
+    pub update: RecordedUpdate,
+    /// The earlier update, either one of the author's own or one by another member on the same
+    /// day.
+    pub similar_to: RecordedUpdate,
+    /// The Jaccard similarity of the shingles of both updates, between 0 and 1.
+    pub similarity: f64,
+}
+
+/// Compares every update in `today` with the author's own updates in `recent`, and with the
+/// updates that other members posted earlier today. Returns the updates whose closest match is at
+/// least as similar as `threshold`.
+pub fn find_similar(
+    today: &[RecordedUpdate],
+    recent: &[RecordedUpdate],
+    rules: &RuleSet,
+    threshold: f64,
+) -> Vec<SimilarUpdate> {
+    let today_shingles: Vec<_> = today.iter().map(|u| shingles(u, rules)).collect();
+    let recent_shingles: Vec<_> = recent.iter().map(|u| shingles(u, rules)).collect();
+    let mut similar = vec![];
+
+    for (update, update_shingles) in today.iter().zip(&today_shingles) {
+        let own = recent
+            .iter()
+            .zip(&recent_shingles)
+            .filter(|(other, _)| other.author_id == update.author_id);
+        // Only earlier updates by others are considered, so that the copy is flagged and not the
+        // original.
+        let others = today.iter().zip(&today_shingles).filter(|(other, _)| {
+            other.author_id != update.author_id && other.timestamp < update.timestamp
+        });
+
+        let closest = own
+            .chain(others)
+            .map(|(other, other_shingles)| (other, jaccard(update_shingles, other_shingles)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((other, similarity)) = closest {
+            if similarity >= threshold {
+                similar.push(SimilarUpdate {
+                    update: update.clone(),
+                    similar_to: other.clone(),
+                    similarity,
+                });
+            }
+        }
+    }
+
+    similar
+}
+
+/// Returns the shingles of the part of `update` that members actually write themselves. The
+/// parsed sections are used when available, and otherwise every line except the greeting and the
+/// sign-off, which every update shares.
+fn shingles(update: &RecordedUpdate, rules: &RuleSet) -> HashSet<String> {
+    let parsed = &update.parsed;
+    let text = if parsed.is_empty() {
+        let boilerplate: Vec<String> = [&rules.greeting, &rules.sign_off]
+            .into_iter()
+            .flatten()
+            .map(|phrase| phrase.to_lowercase())
+            .collect();
+        update
+            .content
+            .to_lowercase()
+            .lines()
+            .filter(|line| !boilerplate.iter().any(|phrase| line.contains(phrase)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        [&parsed.done, &parsed.planned, &parsed.blockers]
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect();
+    if words.len() < SHINGLE_SIZE {
+        return words.into_iter().collect();
+    }
+
+    words
+        .windows(SHINGLE_SIZE)
+        .map(|window| window.join(" "))
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::{ChannelId, MessageId, Timestamp, UserId};
+
+    use super::*;
+    use crate::updates::parser;
+
+    fn update(id: u64, author: u64, hours_ago: i64, content: &str) -> RecordedUpdate {
+        let timestamp = chrono::Utc::now() - chrono::Duration::hours(hours_ago);
+        RecordedUpdate {
+            message_id: MessageId::new(id),
+            channel_id: ChannelId::new(1),
+            guild_id: None,
+            author_id: UserId::new(author),
+            author_name: format!("member{}", author),
+            content: content.to_string(),
+            timestamp: Timestamp::from(timestamp),
+            parsed: parser::parse(content, &RuleSet::default()),
+        }
+    }
+
+    const UPDATE: &str = "Namah Shivaya\nWhat I did\n- Fixed the login page and wrote tests \
+                          for the session handling\nRegards";
+
+    #[test]
+    fn flags_repeats_of_own_updates() {
+        let today = [update(2, 1, 1, UPDATE)];
+        let recent = [update(1, 1, 25, UPDATE)];
+        let similar = find_similar(&today, &recent, &RuleSet::default(), 0.8);
+
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].similar_to.message_id, MessageId::new(1));
+        assert!((similar[0].similarity - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn flags_the_copy_and_not_the_original() {
+        let today = [update(1, 1, 2, UPDATE), update(2, 2, 1, UPDATE)];
+        let similar = find_similar(&today, &[], &RuleSet::default(), 0.8);
+
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].update.author_id, UserId::new(2));
+    }
+
+    #[test]
+    fn ignores_shared_boilerplate_and_other_members_history() {
+        let today = [update(
+            3,
+            1,
+            1,
+            "Namah Shivaya\nWhat I did\n- Designed the new dashboard layout in Figma\nRegards",
+        )];
+        let recent = [update(1, 1, 25, UPDATE), update(2, 2, 25, UPDATE)];
+
+        assert!(find_similar(&today, &recent, &RuleSet::default(), 0.8).is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+/*
+amFOSS Daemon: A discord bot for the amFOSS Discord server.
+Copyright (C) 2024 amFOSS
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+use anyhow::Context as _;
+use chrono::{Datelike, Duration, NaiveDate};
+use image::{ImageFormat, Rgb, RgbImage};
+
+use std::io::Cursor;
+
+/// Weeks shown, so that a full year fits.
+const WEEKS: i64 = 53;
+const CELL: u32 = 11;
+const GAP: u32 = 3;
+const MARGIN: u32 = 10;
+const BACKGROUND: Rgb<u8> = Rgb([0x0d, 0x11, 0x17]);
+
+/// Colours with no data, then from the lowest to the highest rate.
+pub const EMPTY: Rgb<u8> = Rgb([0x16, 0x1b, 0x22]);
+pub const LEVELS: [Rgb<u8>; 4] = [
+    Rgb([0x0e, 0x44, 0x29]),
+    Rgb([0x00, 0x6d, 0x32]),
+    Rgb([0x26, 0xa6, 0x41]),
+    Rgb([0x39, 0xd3, 0x53]),
+];
+pub const MISSED: Rgb<u8> = Rgb([0x8e, 0x1f, 0x2b]);
+pub const FROZEN: Rgb<u8> = Rgb([0x38, 0x8b, 0xfd]);
+
+/// Returns the colour of `LEVELS` for a rate between 0 and 1.
+pub fn level(rate: f64) -> Rgb<u8> {
+    let index = (rate * LEVELS.len() as f64).ceil() as usize;
+    index
+        .checked_sub(1)
+        .map_or(EMPTY, |i| LEVELS[i.min(LEVELS.len() - 1)])
+}
+
+/// Renders a GitHub-style calendar of the year up to `end` as a PNG, with a column per week
+/// starting on Sunday and a cell per day coloured by `colour_of`.
+pub fn render(end: NaiveDate, colour_of: impl Fn(NaiveDate) -> Rgb<u8>) -> anyhow::Result<Vec<u8>> {
+    let last_sunday = end - Duration::days(end.weekday().num_days_from_sunday() as i64);
+    let start = last_sunday - Duration::weeks(WEEKS - 1);
+
+    let width = 2 * MARGIN + WEEKS as u32 * (CELL + GAP) - GAP;
+    let height = 2 * MARGIN + 7 * (CELL + GAP) - GAP;
+    let mut image = RgbImage::from_pixel(width, height, BACKGROUND);
+
+    for date in start.iter_days().take_while(|date| *date <= end) {
+        let week = ((date - start).num_days() / 7) as u32;
+        let day = date.weekday().num_days_from_sunday();
+        let (x, y) = (MARGIN + week * (CELL + GAP), MARGIN + day * (CELL + GAP));
+        let colour = colour_of(date);
+        for dx in 0..CELL {
+            for dy in 0..CELL {
+                image.put_pixel(x + dx, y + dy, colour);
+            }
+        }
+    }
+
+    let mut png = Cursor::new(vec![]);
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .context("Failed to encode heatmap")?;
+    Ok(png.into_inner())
+}
@@ -15,33 +15,285 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
-use chrono::{Datelike, Local, TimeZone};
-use chrono_tz::Asia::Kolkata;
+use anyhow::{anyhow, Context as _};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::{Asia::Kolkata, Tz};
+use serde::Deserialize;
 use tracing::debug;
 
 use std::time::Duration;
 
-pub fn time_until(hour: u32, minute: u32) -> Duration {
-    debug!(
-        "time_until called with args hour: {}, minute: {}",
-        hour, minute
-    );
+/// When the status update report runs and which messages it counts. Every time in here is
+/// interpreted in [`Schedule::timezone`], never in the timezone of the host.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "ScheduleConfig")]
+pub struct Schedule {
+    pub timezone: Tz,
+    pub report_time: NaiveTime,
+    /// The collection window opens at the last `window_start` before it closes.
+    pub window_start: NaiveTime,
+    /// The collection window closes at the last `window_end` at or before the report.
+    pub window_end: NaiveTime,
+    /// When members who haven't sent their update yet are reminded.
+    pub reminder_time: NaiveTime,
+    /// How long after the window closes updates are still accepted, flagged as late. The report
+    /// is delayed by the same amount so that it can count them.
+    pub grace: chrono::Duration,
+}
+
+/// The textual form of a [`Schedule`], as written in the ENV or a profile. Times are written as
+/// `HH:MM` and the timezone by its IANA name. Anything left out takes its [`Default`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    timezone: Option<String>,
+    report_time: Option<String>,
+    window_start: Option<String>,
+    window_end: Option<String>,
+    reminder_time: Option<String>,
+    grace_minutes: Option<u32>,
+}
+
+impl TryFrom<ScheduleConfig> for Schedule {
+    type Error = anyhow::Error;
+
+    fn try_from(config: ScheduleConfig) -> anyhow::Result<Self> {
+        let mut schedule = Self::default();
+        if let Some(timezone) = config.timezone {
+            schedule.timezone = timezone
+                .parse()
+                .map_err(|e| anyhow!("Failed to parse timezone: {}", e))?;
+        }
+        for (name, value, field) in [
+            ("report time", config.report_time, &mut schedule.report_time),
+            (
+                "window start",
+                config.window_start,
+                &mut schedule.window_start,
+            ),
+            ("window end", config.window_end, &mut schedule.window_end),
+            (
+                "reminder time",
+                config.reminder_time,
+                &mut schedule.reminder_time,
+            ),
+        ] {
+            if let Some(value) = value {
+                *field = parse_time(&value).with_context(|| format!("Failed to parse {}", name))?;
+            }
+        }
+
+        if let Some(minutes) = config.grace_minutes {
+            schedule.grace = chrono::Duration::minutes(minutes.into());
+        }
+
+        Ok(schedule)
+    }
+}
+
+/// The span of time a report counts updates from.
+#[derive(Clone, Copy, Debug)]
+pub struct CollectionWindow {
+    pub start: DateTime<Tz>,
+    pub end: DateTime<Tz>,
+    /// Updates sent from `end` up to here are counted as late.
+    pub grace_end: DateTime<Tz>,
+}
+
+impl CollectionWindow {
+    /// Renders the window with Discord timestamps, which every reader sees in their own timezone.
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "<t:{}:f> to <t:{}:f>",
+            self.start.timestamp(),
+            self.end.timestamp()
+        );
+        if self.grace_end > self.end {
+            description.push_str(&format!(
+                " (late until <t:{}:t>)",
+                self.grace_end.timestamp()
+            ));
+        }
+
+        description
+    }
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            timezone: Kolkata,
+            report_time: NaiveTime::from_hms_opt(5, 0, 0).expect("Valid time"),
+            window_start: NaiveTime::from_hms_opt(17, 0, 0).expect("Valid time"),
+            window_end: NaiveTime::from_hms_opt(5, 0, 0).expect("Valid time"),
+            reminder_time: NaiveTime::from_hms_opt(23, 0, 0).expect("Valid time"),
+            grace: chrono::Duration::zero(),
+        }
+    }
+}
+
+impl Schedule {
+    /// Reads `TIMEZONE`, `REPORT_TIME`, `WINDOW_START`, `WINDOW_END`, `REMINDER_TIME` and
+    /// `GRACE_MINUTES` from the ENV, using the [`Default`] for any that are missing.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let config = ScheduleConfig {
+            timezone: var("TIMEZONE"),
+            report_time: var("REPORT_TIME"),
+            window_start: var("WINDOW_START"),
+            window_end: var("WINDOW_END"),
+            reminder_time: var("REMINDER_TIME"),
+            grace_minutes: var("GRACE_MINUTES")
+                .map(|minutes| minutes.parse())
+                .transpose()
+                .context("Failed to parse GRACE_MINUTES")?,
+        };
+        let schedule = Self::try_from(config)?;
+
+        debug!("Schedule: {:?}", schedule);
+        Ok(schedule)
+    }
+
+    pub fn now(&self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self.timezone)
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
 
-    let now = Local::now().with_timezone(&Kolkata);
-    let today_run = Kolkata
-        .with_ymd_and_hms(now.year(), now.month(), now.day(), hour, minute, 0)
-        .single()
-        .expect("Valid datetime must be created");
+    /// Returns the time until the next report is due, counting its grace period.
+    pub fn time_until_report(&self) -> Duration {
+        let next = self.last_due_report() + chrono::Duration::days(1);
+        duration_until(self.report_at(next) + self.grace)
+    }
 
-    let next_run = if now < today_run {
-        today_run
-    } else {
-        today_run + chrono::Duration::days(1)
-    };
+    /// Returns the date of the most recent report that is due, counting its grace period. A long
+    /// grace period can push a report past midnight, into the next day.
+    pub fn last_due_report(&self) -> NaiveDate {
+        let now = self.now();
+        let mut date = now.date_naive();
+        while self.report_at(date) + self.grace > now {
+            date -= chrono::Duration::days(1);
+        }
 
-    debug!("now: {}, today_run: {}", now, today_run);
+        date
+    }
+
+    /// Returns the time until the clock next reads `time`.
+    pub fn time_until_next(&self, time: NaiveTime) -> Duration {
+        duration_until(self.next_occurrence(time))
+    }
+
+    /// Returns the next time the clock reads `time`.
+    pub fn next_occurrence(&self, time: NaiveTime) -> DateTime<Tz> {
+        let now = self.now();
+        let today_run = self.at(now.date_naive(), time);
+
+        if now < today_run {
+            today_run
+        } else {
+            self.at(now.date_naive() + chrono::Duration::days(1), time)
+        }
+    }
+
+    /// Returns the time until the next `weekday` at `time`.
+    pub fn time_until_weekday(&self, weekday: Weekday, time: NaiveTime) -> Duration {
+        let mut next_run = self.next_occurrence(time);
+        while next_run.weekday() != weekday {
+            next_run = self.at(next_run.date_naive() + chrono::Duration::days(1), time);
+        }
+
+        duration_until(next_run)
+    }
+
+    /// Returns the time until the next `day` of a month at `time`.
+    pub fn time_until_day_of_month(&self, day: u32, time: NaiveTime) -> Duration {
+        let mut next_run = self.next_occurrence(time);
+        while next_run.day() != day {
+            next_run = self.at(next_run.date_naive() + chrono::Duration::days(1), time);
+        }
+
+        duration_until(next_run)
+    }
+
+    /// Returns the window of the report that runs at `report`.
+    pub fn window_for(&self, report: DateTime<Tz>) -> CollectionWindow {
+        let date = report.date_naive();
+        let mut end = self.at(date, self.window_end);
+        if end > report {
+            end = self.at(date - chrono::Duration::days(1), self.window_end);
+        }
+        let mut start = self.at(end.date_naive(), self.window_start);
+        if start >= end {
+            start = self.at(
+                end.date_naive() - chrono::Duration::days(1),
+                self.window_start,
+            );
+        }
+
+        CollectionWindow {
+            start,
+            end,
+            grace_end: end + self.grace,
+        }
+    }
+
+    /// Returns when the report of `date` runs, not counting the grace period.
+    pub fn report_at(&self, date: NaiveDate) -> DateTime<Tz> {
+        self.at(date, self.report_time)
+    }
+
+    /// Returns the date of the report whose window, including its grace period, contains `time`.
+    pub fn report_date_of(&self, time: DateTime<Utc>) -> Option<NaiveDate> {
+        let date = time.with_timezone(&self.timezone).date_naive();
+        [date, date + chrono::Duration::days(1)]
+            .into_iter()
+            .find(|date| {
+                let window = self.window_for(self.report_at(*date));
+                window.start <= time && time < window.grace_end
+            })
+    }
+
+    /// Returns the window of the most recent report, or of one running right now.
+    pub fn current_window(&self) -> CollectionWindow {
+        let report = self.report_at(self.today());
+        if report > self.now() {
+            self.window_for(report - chrono::Duration::days(1))
+        } else {
+            self.window_for(report)
+        }
+    }
+
+    /// Returns the window of the next report, which may still be open.
+    pub fn upcoming_window(&self) -> CollectionWindow {
+        self.window_for(self.next_occurrence(self.report_time))
+    }
+
+    /// Returns `date` at `time`. Times that are skipped by a DST transition are moved forward.
+    fn at(&self, date: NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+        let mut naive = date.and_time(time);
+        loop {
+            if let Some(datetime) = self.timezone.from_local_datetime(&naive).earliest() {
+                return datetime;
+            }
+            naive += chrono::Duration::minutes(30);
+        }
+    }
+}
+
+fn duration_until(next_run: DateTime<Tz>) -> Duration {
+    let now = Utc::now();
+    debug!("now: {}, next_run: {}", now, next_run);
 
     let duration = next_run.signed_duration_since(now);
     debug!("duration: {}", duration);
-    Duration::from_secs(duration.num_seconds().max(0) as u64)
+    // Millisecond precision so the task never wakes up just before its run time and runs twice.
+    Duration::from_millis(duration.num_milliseconds().max(0) as u64)
+}
+
+/// Parses a time of day written as `HH:MM`.
+pub fn parse_time(value: &str) -> anyhow::Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .with_context(|| format!("Invalid time {}, expected HH:MM", value))
 }